@@ -4,13 +4,14 @@ fn main() {
     // 设置应用程序图标 (可选，如果你有 icon.ico 文件放在根目录)
     res.set_icon("icon.ico");
     
-    // 关键：请求管理员权限
+    // 通过 lockfile 连接客户端，无需管理员权限
+    // 若只能依赖命令行扫描，可手动以管理员身份运行
     res.set_manifest(r#"
 <assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
 <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
     <security>
         <requestedPrivileges>
-            <requestedExecutionLevel level="requireAdministrator" uiAccess="false" />
+            <requestedExecutionLevel level="asInvoker" uiAccess="false" />
         </requestedPrivileges>
     </security>
 </trustInfo>
@@ -1,28 +1,48 @@
-use reqwest::Client;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use crate::lcu::lcu_request;
-use crate::models::{ActionState, GuiMsg, Hero, LcuConnection, Settings};
-use crate::utils::lookup_hero_name_by_id;
+use crate::lcu::{LcuApi, LcuError};
+use crate::models::{ActionState, ChampSelectState, GuiMsg, Hero, Settings};
+use crate::utils::{load_tier_scores, lookup_hero_name_by_id};
+
+use super::chat::handle_chat;
+use super::draft::draft_snapshot;
+use super::loadout::handle_loadout;
+use super::swaps::handle_swaps;
+use super::trades::handle_trades;
+
+/// 锁定请求被拒绝后的重试间隔
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// 可选/可禁用英雄查询失败后的重试间隔与最多查询次数
+const AVAILABILITY_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+const AVAILABILITY_MAX_ATTEMPTS: u32 = 3;
+/// 斗魂竞技场的游戏模式与地图
+const ARENA_GAME_MODE: &str = "CHERRY";
+const ARENA_MAP_ID: i64 = 30;
 
 /// 英雄选择阶段的主处理函数
-/// 处理大乱斗板凳席和峡谷/排位的 Ban & Pick 逻辑
+/// 处理大乱斗板凳席、峡谷/排位和斗魂竞技场的 Ban & Pick 逻辑
+///
+/// `pushed_session` 为事件推送的最新会话，存在时不再重复请求
+///
+/// `queue_id` 为当前队列，斗魂竞技场中队列方案叠加在竞技场设置之上
 pub async fn handle_champ_select(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
+    queue_id: Option<i64>,
     tx: &crossbeam_channel::Sender<GuiMsg>,
-    last_bench_ids: &mut Vec<i32>,
-    handled_actions: &mut HashMap<i64, ActionState>,
+    pushed_session: Option<&serde_json::Value>,
+    cs: &mut ChampSelectState,
     shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
 ) {
-    let session_json =
-        match lcu_request(client, conn, "GET", "/lol-champ-select/v1/session", None).await {
+    let session_json = match pushed_session {
+        Some(v) => v.clone(),
+        None => match api.request("GET", "/lol-champ-select/v1/session", None).await {
             Ok(v) => v,
             Err(_) => return,
-        };
+        },
+    };
 
     let local_cell_id = session_json
         .get("localPlayerCellId")
@@ -32,31 +52,91 @@ pub async fn handle_champ_select(
         return;
     }
 
-    // --- 大乱斗板凳席模式 ---
-    if session_json.get("benchEnabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-        handle_aram_bench(client, conn, settings, tx, session_json, last_bench_ids, local_cell_id, shared_heroes).await;
-        return;
+    let bench_enabled = session_json.get("benchEnabled").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    // 每次英雄选择判断一次是否为斗魂竞技场
+    if !bench_enabled && !cs.mode_checked {
+        cs.mode_checked = true;
+        cs.arena = detect_arena(api, &session_json).await;
+        if cs.arena {
+            tx.send(GuiMsg::Log("斗魂竞技场：使用竞技场的预选/禁用列表".to_string())).ok();
+        }
     }
+    let arena_settings;
+    let settings = if cs.arena {
+        arena_settings = settings.for_arena(queue_id);
+        &arena_settings
+    } else {
+        settings
+    };
 
-    // --- 峡谷/排位 Ban & Pick 逻辑 ---
-    handle_sr_pick_ban(client, conn, settings, tx, session_json, handled_actions, shared_heroes, local_cell_id).await;
+    // 我方英雄确定后设置召唤师技能等
+    handle_loadout(api, settings, tx, &session_json, local_cell_id, cs, shared_heroes).await;
+    handle_swaps(api, settings, tx, &session_json, local_cell_id, cs).await;
+    handle_trades(api, settings, tx, &session_json, local_cell_id, cs, shared_heroes).await;
+    handle_chat(api, settings, tx, &session_json, local_cell_id, cs, shared_heroes).await;
+
+    if bench_enabled {
+        // --- 大乱斗板凳席模式 ---
+        let session = session_json.clone();
+        handle_aram_bench(api, settings, tx, session, cs, local_cell_id, shared_heroes).await;
+    } else {
+        // --- 峡谷/排位/竞技场 Ban & Pick 逻辑 ---
+        let session = session_json.clone();
+        handle_sr_pick_ban(api, settings, tx, session, cs, shared_heroes, local_cell_id).await;
+    }
+
+    // 处理完后再生成快照，包含本次计划的锁定时间
+    tx.send(GuiMsg::Draft(Some(draft_snapshot(&session_json, local_cell_id, cs)))).ok();
+}
+
+/// 是否为斗魂竞技场
+///
+/// 以游戏流程中的模式/地图为准；取不到时根据会话判断：
+/// 没有分路且我方只有自己和双人队友。
+async fn detect_arena(api: &impl LcuApi, session: &serde_json::Value) -> bool {
+    if let Ok(flow) = api.request("GET", "/lol-gameflow/v1/session", None).await {
+        let map = flow.get("map");
+        if let Some(mode) = map.and_then(|m| m.get("gameMode")).and_then(|v| v.as_str()) {
+            return mode == ARENA_GAME_MODE;
+        }
+        if let Some(id) = map.and_then(|m| m.get("id")).and_then(|v| v.as_i64()) {
+            return id == ARENA_MAP_ID;
+        }
+    }
+    session
+        .get("myTeam")
+        .and_then(|v| v.as_array())
+        .is_some_and(|tm| {
+            tm.len() == 2
+                && tm.iter().all(|m| {
+                    m.get("assignedPosition").and_then(|s| s.as_str()).unwrap_or("").is_empty()
+                })
+        })
 }
 
-/// 峡谷/排位的 Ban & Pick 核心逻辑
+/// 峡谷/排位的 Ban & Pick 核心逻辑，斗魂竞技场使用 "arena" 位置下的列表
+///
+/// 竞技场中 myTeam 只有双人队友，其意向英雄和禁用同样会被避开。
 /// 
 /// 关键设计：使用定时锁定机制
 /// - 当 isInProgress 首次变为 true 且 timer.phase 正确时，计算锁定时间点
 /// - 后续轮询检查是否到达锁定时间点，到达则执行锁定
 async fn handle_sr_pick_ban(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     session_json: serde_json::Value,
-    handled_actions: &mut HashMap<i64, ActionState>,
+    cs: &mut ChampSelectState,
     shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
     local_cell_id: i64,
 ) {
+    // 每次英雄选择查询一次账号可用的英雄，失败时稍后重试
+    let retry_due = cs.availability_retry_at.is_none_or(|t| Instant::now() >= t);
+    if !cs.availability_checked && retry_due {
+        load_champion_availability(api, tx, cs).await;
+    }
+
     // 获取计时器信息
     let timer_obj = session_json.get("timer");
     let time_left_ms = timer_obj
@@ -74,28 +154,27 @@ async fn handle_sr_pick_ban(
         .unwrap_or("");
 
     // 获取自己的位置
-    let mut my_pos = String::new();
-    // 收集队友的意向英雄（用于避免禁用队友想玩的英雄）
-    let mut teammate_intents: HashSet<i32> = HashSet::new();
-
-    if let Some(tm) = session_json.get("myTeam").and_then(|v| v.as_array()) {
-        for m in tm {
-            let cid = m.get("cellId").and_then(|x| x.as_i64()).unwrap_or(-2);
-            if cid == local_cell_id {
-                my_pos = m.get("assignedPosition")
-                    .and_then(|s| s.as_str())
-                    .unwrap_or("")
-                    .to_lowercase();
-            } else {
-                // 收集队友意向
-                if let Some(intent) = m.get("championPickIntent").and_then(|v| v.as_i64()) {
-                    if intent > 0 {
-                        teammate_intents.insert(intent as i32);
-                    }
-                }
-            }
-        }
-    }
+    let my_pos = if cs.arena {
+        "arena".to_string()
+    } else {
+        session_json
+            .get("myTeam")
+            .and_then(|v| v.as_array())
+            .and_then(|tm| {
+                tm.iter()
+                    .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(local_cell_id))
+            })
+            .and_then(|m| m.get("assignedPosition"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_lowercase()
+    };
+    let draft = DraftInfo::from_session(
+        &session_json,
+        local_cell_id,
+        cs.pickable.as_ref(),
+        cs.bannable.as_ref(),
+    );
 
     let now = Instant::now();
 
@@ -117,7 +196,7 @@ async fn handle_sr_pick_ban(
                     let current_champ_id = act.get("championId").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
 
                     // 获取/创建该 action 的状态
-                    let state = handled_actions.entry(action_id).or_default();
+                    let state = cs.handled_actions.entry(action_id).or_default();
 
                     // 如果已完成，标记并跳过
                     if is_completed {
@@ -137,9 +216,10 @@ async fn handle_sr_pick_ban(
 
                     // ========== BAN 阶段处理 ==========
                     if type_str == "ban" {
+                        let candidates = settings.sr_bans.get(&my_pos).map(Vec::as_slice).unwrap_or(&[]);
                         handle_ban_action(
-                            client, conn, settings, tx, shared_heroes,
-                            &my_pos, &teammate_intents,
+                            api, settings, tx, shared_heroes,
+                            candidates, &draft,
                             action_id, is_in_progress, current_champ_id,
                             time_left_ms, timer_phase,
                             state, should_act, now,
@@ -147,9 +227,10 @@ async fn handle_sr_pick_ban(
                     }
                     // ========== PICK 阶段处理 ==========
                     else if type_str == "pick" {
+                        let candidates = settings.sr_picks.get(&my_pos).map(Vec::as_slice).unwrap_or(&[]);
                         handle_pick_action(
-                            client, conn, settings, tx, shared_heroes,
-                            &my_pos,
+                            api, settings, tx, shared_heroes,
+                            candidates, &draft,
                             action_id, is_in_progress, current_champ_id,
                             time_left_ms, timer_phase,
                             state, should_act, now,
@@ -161,19 +242,140 @@ async fn handle_sr_pick_ban(
     }
 }
 
+/// 当前对局中已不可选的英雄
+struct DraftInfo<'a> {
+    /// 双方禁用列表与已完成的禁用
+    banned: HashSet<i32>,
+    /// 已锁定的英雄
+    picked: HashSet<i32>,
+    /// 队友的意向英雄与正在高亮的英雄
+    teammate_intents: HashSet<i32>,
+    /// 队友正在高亮、尚未锁定的禁用
+    teammate_bans: HashSet<i32>,
+    /// 账号可选的英雄，None 表示未知（不过滤）
+    pickable: Option<&'a HashSet<i32>>,
+    /// 可禁用的英雄，None 表示未知（不过滤）
+    bannable: Option<&'a HashSet<i32>>,
+}
+
+impl<'a> DraftInfo<'a> {
+    fn from_session(
+        session: &serde_json::Value,
+        local_cell_id: i64,
+        pickable: Option<&'a HashSet<i32>>,
+        bannable: Option<&'a HashSet<i32>>,
+    ) -> Self {
+        let mut banned = HashSet::new();
+        let mut picked = HashSet::new();
+        let mut teammate_intents = HashSet::new();
+        let mut teammate_bans = HashSet::new();
+        let ids = |v: &serde_json::Value| -> Vec<i32> {
+            v.as_array()
+                .map(|a| a.iter().filter_map(|x| x.as_i64()).map(|x| x as i32).collect())
+                .unwrap_or_default()
+        };
+
+        if let Some(bans) = session.get("bans") {
+            banned.extend(ids(&bans["myTeamBans"]));
+            banned.extend(ids(&bans["theirTeamBans"]));
+        }
+
+        let mut teammate_cells = HashSet::new();
+        if let Some(tm) = session.get("myTeam").and_then(|v| v.as_array()) {
+            for m in tm {
+                let cid = m.get("cellId").and_then(|x| x.as_i64()).unwrap_or(-2);
+                if cid == local_cell_id {
+                    continue;
+                }
+                teammate_cells.insert(cid);
+                if let Some(intent) = m.get("championPickIntent").and_then(|v| v.as_i64()) {
+                    teammate_intents.insert(intent as i32);
+                }
+            }
+        }
+
+        let actions = session.get("actions").and_then(|v| v.as_array());
+        for act in actions.into_iter().flatten().filter_map(|g| g.as_array()).flatten() {
+            let champ = act.get("championId").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            let completed = act.get("completed").and_then(|v| v.as_bool()).unwrap_or(false);
+            let actor = act.get("actorCellId").and_then(|v| v.as_i64()).unwrap_or(-9);
+            match act.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+                "ban" if completed => {
+                    banned.insert(champ);
+                }
+                "pick" if completed => {
+                    picked.insert(champ);
+                }
+                "pick" if teammate_cells.contains(&actor) => {
+                    teammate_intents.insert(champ);
+                }
+                "ban" if teammate_cells.contains(&actor) => {
+                    teammate_bans.insert(champ);
+                }
+                _ => {}
+            }
+        }
+
+        for set in [&mut banned, &mut picked, &mut teammate_intents, &mut teammate_bans] {
+            set.retain(|&id| id > 0);
+        }
+        Self { banned, picked, teammate_intents, teammate_bans, pickable, bannable }
+    }
+
+    /// 该英雄是否已被禁用或锁定
+    fn is_taken(&self, champ_id: i32) -> bool {
+        self.banned.contains(&champ_id) || self.picked.contains(&champ_id)
+    }
+
+    /// 按优先级返回第一个可以选择的英雄，没有则返回 0
+    fn first_pick(&self, candidates: &[i32]) -> i32 {
+        candidates
+            .iter()
+            .copied()
+            .find(|&id| {
+                id > 0
+                    && self.pickable.is_none_or(|p| p.contains(&id))
+                    && !self.is_taken(id)
+                    && !self.teammate_intents.contains(&id)
+            })
+            .unwrap_or(0)
+    }
+
+    /// 该英雄是否已被禁用、锁定或正被队友禁用
+    fn is_ban_wasted(&self, champ_id: i32) -> bool {
+        self.is_taken(champ_id) || self.teammate_bans.contains(&champ_id)
+    }
+
+    /// 按优先级返回第一个值得禁用的英雄（不禁用队友想玩的英雄），没有则返回 0
+    fn first_ban(&self, candidates: &[i32]) -> i32 {
+        candidates
+            .iter()
+            .copied()
+            .find(|&id| {
+                id > 0
+                    && self.bannable.is_none_or(|b| b.contains(&id))
+                    && !self.is_ban_wasted(id)
+                    && !self.teammate_intents.contains(&id)
+            })
+            .unwrap_or(0)
+    }
+}
+
 /// 处理 Ban 阶段的 action
+///
+/// `candidates` 为当前位置按优先级排列的禁用列表，依次跳过已禁用、已锁定、
+/// 队友正在禁用以及队友想玩的英雄。
 /// 
 /// 定时锁定逻辑：
 /// 1. 只在非 BAN_PICK 阶段（Ban Intent）时才启动锁定定时器
 /// 2. BAN_PICK 阶段只能高亮，不能锁定
 async fn handle_ban_action(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
-    my_pos: &str,
-    teammate_intents: &HashSet<i32>,
+    candidates: &[i32],
+    draft: &DraftInfo<'_>,
     action_id: i64,
     is_in_progress: bool,
     current_champ_id: i32,
@@ -188,26 +390,12 @@ async fn handle_ban_action(
         return;
     }
 
-    // 获取预设的禁用目标英雄ID
-    let mut preset_target = *settings.sr_bans.get(my_pos).unwrap_or(&0);
-    
-    // 如果预设目标是队友想玩的英雄，尝试从备选池中选择其他英雄
-    if preset_target > 0 && teammate_intents.contains(&preset_target) {
-        let alt_pool: Vec<i32> = settings.sr_bans
-            .values()
-            .cloned()
-            .filter(|&id| id > 0 && !teammate_intents.contains(&id))
-            .collect();
-        if !alt_pool.is_empty() {
-            preset_target = alt_pool[(action_id as usize) % alt_pool.len()];
-            tx.send(GuiMsg::Log(format!(
-                "禁用目标冲突，改为: {}",
-                lookup_hero_name_by_id(shared_heroes, preset_target)
-            ))).ok();
-        } else {
-            preset_target = 0;
-        }
-    }
+    // 获取当前值得禁用的最高优先级英雄
+    let preset_target = draft.first_ban(candidates);
+    // 当前高亮为空、已被禁用/锁定，或是列表中优先级较低的英雄时需要重新高亮
+    let needs_hover = current_champ_id == 0
+        || draft.is_ban_wasted(current_champ_id)
+        || (candidates.contains(&current_champ_id) && current_champ_id != preset_target);
 
     // 检查是否是意向阶段（PLANNING = 所有人同时选择意向）
     // 只有 PLANNING 阶段不启动定时器，BAN_PICK 是实际 Ban 阶段可以锁定
@@ -245,23 +433,28 @@ async fn handle_ban_action(
         }
         
         // 2. 尝试高亮预设目标（无论什么阶段都可以高亮）
-        if current_champ_id == 0 && preset_target > 0 && should_act {
-            let _ = lcu_patch_action(client, conn, action_id, preset_target, false).await;
+        if needs_hover && preset_target > 0 && should_act {
+            let result = lcu_patch_action(api, action_id, preset_target, false).await;
             state.last_act_time = Some(now);
-            if !state.hovered {
-                tx.send(GuiMsg::Log(format!(
-                    "准备禁用: {} (阶段: {})",
-                    lookup_hero_name_by_id(shared_heroes, preset_target),
-                    timer_phase
-                ))).ok();
-                state.hovered = true;
+            match result {
+                Ok(()) if state.target != preset_target => {
+                    tx.send(GuiMsg::Log(format!(
+                        "准备禁用: {} (阶段: {})",
+                        lookup_hero_name_by_id(shared_heroes, preset_target),
+                        timer_phase
+                    ))).ok();
+                    state.hovered = true;
+                    state.target = preset_target;
+                }
+                Ok(()) => {}
+                Err(e) => report_action_error(tx, state, "高亮禁用", &e),
             }
         }
         
         // 3. 检查是否到达锁定时间点（不再检查 can_lock，与 Pick 一致）
         if let Some(lock_at) = state.lock_scheduled_at {
             if now >= lock_at {
-                let lock_target = if current_champ_id > 0 { current_champ_id } else { preset_target };
+                let lock_target = if current_champ_id > 0 && !needs_hover { current_champ_id } else { preset_target };
                 
                 if lock_target > 0 {
                     tx.send(GuiMsg::Log(format!(
@@ -269,9 +462,16 @@ async fn handle_ban_action(
                         lookup_hero_name_by_id(shared_heroes, lock_target),
                         timer_phase
                     ))).ok();
-                    let _ = lcu_patch_action(client, conn, action_id, lock_target, true).await;
-                    state.completed = true;
-                    state.lock_scheduled_at = None;
+                    match lcu_patch_action(api, action_id, lock_target, true).await {
+                        Ok(()) => {
+                            state.completed = true;
+                            state.lock_scheduled_at = None;
+                        }
+                        Err(e) => {
+                            report_action_error(tx, state, "锁定禁用", &e);
+                            state.lock_scheduled_at = Some(now + LOCK_RETRY_INTERVAL);
+                        }
+                    }
                 }
             }
         }
@@ -279,22 +479,31 @@ async fn handle_ban_action(
         // 还没轮到我 Ban，清除定时并尝试预高亮
         state.lock_scheduled_at = None;
         
-        if preset_target > 0 && current_champ_id != preset_target && should_act && !state.hovered {
-            let _ = lcu_patch_action(client, conn, action_id, preset_target, false).await;
+        if preset_target > 0 && current_champ_id != preset_target && should_act && state.target != preset_target {
+            let result = lcu_patch_action(api, action_id, preset_target, false).await;
             state.last_act_time = Some(now);
-            state.hovered = true;
+            match result {
+                Ok(()) => {
+                    state.hovered = true;
+                    state.target = preset_target;
+                }
+                Err(e) => report_action_error(tx, state, "高亮禁用", &e),
+            }
         }
     }
 }
 
 /// 处理 Pick 阶段的 action
+///
+/// `candidates` 为当前位置按优先级排列的预选列表，依次跳过已禁用、已锁定
+/// 以及队友想玩的英雄。玩家手动高亮了列表之外的英雄时不做干预。
 async fn handle_pick_action(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
-    my_pos: &str,
+    candidates: &[i32],
+    draft: &DraftInfo<'_>,
     action_id: i64,
     is_in_progress: bool,
     current_champ_id: i32,
@@ -304,8 +513,12 @@ async fn handle_pick_action(
     should_act: bool,
     now: Instant,
 ) {
-    // 获取预设的选择目标英雄ID
-    let preset_target = *settings.sr_picks.get(my_pos).unwrap_or(&0);
+    // 获取当前可选的最高优先级英雄
+    let preset_target = draft.first_pick(candidates);
+    // 当前高亮为空、已不可选，或是列表中优先级较低的英雄时需要重新高亮
+    let needs_hover = current_champ_id == 0
+        || draft.is_taken(current_champ_id)
+        || (candidates.contains(&current_champ_id) && current_champ_id != preset_target);
 
     // 当前轮到我操作 Pick
     if is_in_progress {
@@ -327,85 +540,188 @@ async fn handle_pick_action(
             ))).ok();
         }
         
-        // 2. 尝试高亮预设目标（如果当前没有高亮且启用了预选）
-        if current_champ_id == 0 && preset_target > 0 && should_act && settings.sr_enable {
-            let _ = lcu_patch_action(client, conn, action_id, preset_target, false).await;
+        // 2. 尝试高亮预设目标（如果当前没有合适的高亮且启用了预选）
+        if needs_hover && preset_target > 0 && should_act && settings.sr_enable {
+            let result = lcu_patch_action(api, action_id, preset_target, false).await;
             state.last_act_time = Some(now);
-            if !state.hovered {
-                tx.send(GuiMsg::Log(format!(
-                    "正在预选: {}",
-                    lookup_hero_name_by_id(shared_heroes, preset_target)
-                ))).ok();
-                state.hovered = true;
+            match result {
+                Ok(()) if state.target != preset_target => {
+                    tx.send(GuiMsg::Log(format!(
+                        "正在预选: {}",
+                        lookup_hero_name_by_id(shared_heroes, preset_target)
+                    ))).ok();
+                    state.hovered = true;
+                    state.target = preset_target;
+                }
+                Ok(()) => {}
+                Err(e) => report_action_error(tx, state, "预选", &e),
             }
         }
         
         // 3. 检查是否到达锁定时间点
         if let Some(lock_at) = state.lock_scheduled_at {
             if now >= lock_at {
-                // 锁定当前高亮的英雄
-                let lock_target = if current_champ_id > 0 { current_champ_id } else { preset_target };
+                // 锁定当前高亮的英雄，已不可选时改用预选列表
+                let lock_target = if current_champ_id > 0 && !needs_hover { current_champ_id } else { preset_target };
                 
                 if lock_target > 0 {
                     tx.send(GuiMsg::Log(format!(
                         "执行锁定选择: {}",
                         lookup_hero_name_by_id(shared_heroes, lock_target)
                     ))).ok();
-                    let _ = lcu_patch_action(client, conn, action_id, lock_target, true).await;
-                    state.completed = true;
-                    state.lock_scheduled_at = None;
+                    match lcu_patch_action(api, action_id, lock_target, true).await {
+                        Ok(()) => {
+                            state.completed = true;
+                            state.lock_scheduled_at = None;
+                        }
+                        Err(e) => {
+                            report_action_error(tx, state, "锁定选择", &e);
+                            state.lock_scheduled_at = Some(now + LOCK_RETRY_INTERVAL);
+                        }
+                    }
                 }
             }
         }
     } else {
-        // 还没轮到我 Pick，清除定时并展示意向
+        // 还没轮到我 Pick，清除定时并展示意向（目标变化时重新展示）
         state.lock_scheduled_at = None;
         
-        if settings.sr_enable && preset_target > 0 && current_champ_id != preset_target && should_act && !state.hovered {
-            let _ = lcu_patch_action(client, conn, action_id, preset_target, false).await;
+        if settings.sr_enable && preset_target > 0 && current_champ_id != preset_target && should_act && state.target != preset_target {
+            let result = lcu_patch_action(api, action_id, preset_target, false).await;
             state.last_act_time = Some(now);
-            tx.send(GuiMsg::Log(format!(
-                "展示意向: {}",
-                lookup_hero_name_by_id(shared_heroes, preset_target)
-            ))).ok();
-            state.hovered = true;
+            match result {
+                Ok(()) => {
+                    tx.send(GuiMsg::Log(format!(
+                        "展示意向: {}",
+                        lookup_hero_name_by_id(shared_heroes, preset_target)
+                    ))).ok();
+                    state.hovered = true;
+                    state.target = preset_target;
+                }
+                Err(e) => report_action_error(tx, state, "展示意向", &e),
+            }
+        }
+    }
+}
+
+/// 查询账号可选/可禁用的英雄并通知 UI
+///
+/// 只重新查询还未知的列表。选人刚开始时客户端可能返回空列表，按未知处理并稍后重新查询；
+/// 请求失败时同样稍后重试，失败超过次数后按未知处理。
+async fn load_champion_availability(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    cs: &mut ChampSelectState,
+) {
+    let mut failed = false;
+    let mut empty = false;
+    if cs.pickable.is_none() {
+        match fetch_champion_ids(api, tx, "pickable-champion-ids", "可选英雄").await {
+            Ok(Some(ids)) => cs.pickable = Some(ids),
+            Ok(None) => empty = true,
+            Err(()) => failed = true,
+        }
+    }
+    if cs.bannable.is_none() {
+        match fetch_champion_ids(api, tx, "bannable-champion-ids", "可禁用英雄").await {
+            Ok(Some(ids)) => cs.bannable = Some(ids),
+            Ok(None) => empty = true,
+            Err(()) => failed = true,
+        }
+    }
+    if failed {
+        cs.availability_attempts += 1;
+    }
+    if (failed || empty) && cs.availability_attempts < AVAILABILITY_MAX_ATTEMPTS {
+        cs.availability_retry_at = Some(Instant::now() + AVAILABILITY_RETRY_INTERVAL);
+    } else {
+        cs.availability_checked = true;
+    }
+    tx.send(GuiMsg::ChampionAvailability {
+        pickable: cs.pickable.clone(),
+        bannable: cs.bannable.clone(),
+    }).ok();
+}
+
+/// 查询英雄ID列表，返回空列表或不是列表时为 Ok(None)，请求失败时记录日志并返回 Err
+async fn fetch_champion_ids(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    endpoint: &str,
+    what: &str,
+) -> Result<Option<HashSet<i32>>, ()> {
+    match api.request("GET", &format!("/lol-champ-select/v1/{}", endpoint), None).await {
+        Ok(v) => Ok(v
+            .as_array()
+            .map(|a| a.iter().filter_map(|x| x.as_i64()).map(|x| x as i32).collect::<HashSet<_>>())
+            .filter(|ids| !ids.is_empty())),
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("获取{}失败: {}", what, e))).ok();
+            Err(())
         }
     }
 }
 
 /// 发送 PATCH 请求修改 action（选择/禁用英雄）
 async fn lcu_patch_action(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     action_id: i64,
     champ_id: i32,
     completed: bool,
-) -> anyhow::Result<()> {
-    let url = format!("{}/lol-champ-select/v1/session/actions/{}", conn.url, action_id);
+) -> Result<(), LcuError> {
     let body = serde_json::json!({
         "championId": champ_id,
         "completed": completed
     });
-    let _ = client
-        .patch(&url)
-        .header("Authorization", &conn.auth_header)
-        .json(&body)
-        .send()
-        .await;
+    api.request(
+        "PATCH",
+        &format!("/lol-champ-select/v1/session/actions/{}", action_id),
+        Some(body),
+    )
+    .await?;
     Ok(())
 }
 
+/// 记录 action 请求失败，同一错误只提示一次
+fn report_action_error(
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    state: &mut ActionState,
+    what: &str,
+    e: &LcuError,
+) {
+    let msg = format!("{}失败: {}", what, e);
+    if state.last_error.as_deref() != Some(msg.as_str()) {
+        tx.send(GuiMsg::Log(msg.clone())).ok();
+        state.last_error = Some(msg);
+    }
+}
+
 /// 大乱斗板凳席处理
 async fn handle_aram_bench(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     session: serde_json::Value,
-    last_bench_ids: &mut Vec<i32>,
+    cs: &mut ChampSelectState,
     local_cell_id: i64,
     shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
 ) {
+    // 每次英雄选择查询一次剩余重随次数
+    if !cs.reroll_checked {
+        cs.reroll_checked = true;
+        refresh_rerolls(api, tx, cs).await;
+    }
+
+    let mut my_hero = 0;
+    if let Some(tm) = session.get("myTeam").and_then(|x| x.as_array()) {
+        for m in tm {
+            if m.get("cellId").and_then(|x| x.as_i64()).unwrap_or(-2) == local_cell_id {
+                my_hero = m.get("championId").and_then(|x| x.as_i64()).unwrap_or(0) as i32;
+                break;
+            }
+        }
+    }
+
     if let Some(bench) = session.get("benchChampions").and_then(|v| v.as_array()) {
         let current_bench: Vec<i32> = bench
             .iter()
@@ -415,25 +731,15 @@ async fn handle_aram_bench(
         // 检查板凳席是否有变化，通知 UI 更新
         let mut sorted = current_bench.clone();
         sorted.sort();
-        let mut last = last_bench_ids.clone();
+        let mut last = cs.last_bench_ids.clone();
         last.sort();
         if sorted != last {
-            *last_bench_ids = current_bench.clone();
+            cs.last_bench_ids = current_bench.clone();
             tx.send(GuiMsg::BenchUpdate(current_bench.clone())).ok();
         }
 
         // 秒抢逻辑
         if settings.aram_snipe {
-            let mut my_hero = 0;
-            if let Some(tm) = session.get("myTeam").and_then(|x| x.as_array()) {
-                for m in tm {
-                    if m.get("cellId").and_then(|x| x.as_i64()).unwrap_or(-2) == local_cell_id {
-                        my_hero = m.get("championId").and_then(|x| x.as_i64()).unwrap_or(0) as i32;
-                        break;
-                    }
-                }
-            }
-
             // 遍历秒抢列表，按优先级尝试交换
             for &tid in &settings.snipe_list {
                 // 如果已经是目标英雄，停止
@@ -442,21 +748,709 @@ async fn handle_aram_bench(
                 }
                 // 如果目标在板凳席上，执行交换
                 if current_bench.contains(&tid) {
-                    let _ = lcu_request(
-                        client,
-                        conn,
-                        "POST",
-                        &format!("/lol-champ-select/v1/session/bench/swap/{}", tid),
-                        None,
-                    ).await;
-                    // 显示英雄中文名
-                    tx.send(GuiMsg::Log(format!(
-                        "秒抢: {}",
-                        lookup_hero_name_by_id(shared_heroes, tid)
-                    ))).ok();
-                    break;
+                    bench_swap(api, tx, tid, shared_heroes, "秒抢").await;
+                    // 已从板凳席交换，本轮不再重随
+                    return;
+                }
+            }
+        }
+
+        // 评分表：秒选列表中的英雄优先，其余按分数交换
+        if settings.tier_scoring && !(settings.aram_snipe && settings.snipe_list.contains(&my_hero)) {
+            let scores = cs.tier_scores.get_or_insert_with(|| {
+                let (scores, unresolved) = load_tier_scores(shared_heroes);
+                tx.send(GuiMsg::Log(format!("已读取评分表: {} 个英雄", scores.len()))).ok();
+                if !unresolved.is_empty() {
+                    tx.send(GuiMsg::Log(format!("评分表中无法识别的英雄: {}", unresolved.join("、")))).ok();
                 }
+                scores
+            });
+            if let Some(tid) = tier_upgrade(scores, &current_bench, my_hero, settings.tier_threshold) {
+                bench_swap(api, tx, tid, shared_heroes, "评分换英雄").await;
+                return;
             }
         }
     }
-}
\ No newline at end of file
+
+    if settings.auto_reroll {
+        handle_aram_reroll(api, settings, tx, cs, my_hero, shared_heroes).await;
+    }
+}
+
+/// 板凳席上分数比当前英雄至少高出 `threshold` 的最高分英雄
+///
+/// 评分表中没有的英雄不会被换入；当前英雄不在表中时按表中最低分计算。
+fn tier_upgrade(
+    scores: &HashMap<i32, f64>,
+    bench: &[i32],
+    my_hero: i32,
+    threshold: f64,
+) -> Option<i32> {
+    let lowest = scores.values().copied().fold(f64::INFINITY, f64::min);
+    let current = scores.get(&my_hero).copied().unwrap_or(lowest);
+    bench
+        .iter()
+        .filter_map(|c| scores.get(c).map(|&s| (*c, s)))
+        .filter(|&(_, s)| s - current >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(c, _)| c)
+}
+
+/// 与板凳席上的英雄交换
+async fn bench_swap(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    champ_id: i32,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+    what: &str,
+) {
+    let result = api.request(
+        "POST",
+        &format!("/lol-champ-select/v1/session/bench/swap/{}", champ_id),
+        None,
+    ).await;
+    // 显示英雄中文名
+    match result {
+        Ok(_) => tx.send(GuiMsg::Log(format!(
+            "{}: {}",
+            what,
+            lookup_hero_name_by_id(shared_heroes, champ_id)
+        ))).ok(),
+        Err(e) => tx.send(GuiMsg::Log(format!(
+            "{} {} 失败: {}",
+            what,
+            lookup_hero_name_by_id(shared_heroes, champ_id),
+            e
+        ))).ok(),
+    };
+}
+
+/// 大乱斗重随：当前英雄不在保留列表和秒选列表中、板凳席上也没有想要的英雄时使用重随次数
+async fn handle_aram_reroll(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    cs: &mut ChampSelectState,
+    my_hero: i32,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) {
+    if my_hero <= 0
+        || my_hero == cs.rerolled_from
+        || settings.reroll_keep_list.contains(&my_hero)
+        || settings.snipe_list.contains(&my_hero)
+        || cs.rerolls_left.unwrap_or(0) <= 0
+    {
+        return;
+    }
+
+    // 无论成功与否，同一个英雄只尝试一次
+    cs.rerolled_from = my_hero;
+    match api
+        .request("POST", "/lol-champ-select/v1/session/my-selection/reroll", None)
+        .await
+    {
+        Ok(_) => {
+            refresh_rerolls(api, tx, cs).await;
+            tx.send(GuiMsg::Log(format!(
+                "重随: {} (剩余 {} 次)",
+                lookup_hero_name_by_id(shared_heroes, my_hero),
+                cs.rerolls_left.unwrap_or(0)
+            ))).ok();
+        }
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("重随失败: {}", e))).ok();
+        }
+    }
+}
+
+/// 查询剩余重随次数并通知 UI
+async fn refresh_rerolls(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    cs: &mut ChampSelectState,
+) {
+    match api
+        .request("GET", "/lol-summoner/v1/current-summoner/rerollPoints", None)
+        .await
+    {
+        Ok(v) => {
+            cs.rerolls_left = v.get("numberOfRolls").and_then(|n| n.as_i64());
+            if let Some(n) = cs.rerolls_left {
+                tx.send(GuiMsg::RerollsLeft(n)).ok();
+            }
+        }
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("获取重随次数失败: {}", e))).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn heroes() -> Arc<Mutex<HashMap<i32, Hero>>> {
+        Arc::new(Mutex::new(HashMap::new()))
+    }
+
+    fn settings() -> Settings {
+        let mut s = Settings::default();
+        s.sr_picks.insert("middle".into(), vec![103, 7]);
+        s.sr_bans.insert("middle".into(), vec![157, 24]);
+        s
+    }
+
+    /// 构造一个自己在中路、只有一个 action 的峡谷会话
+    fn sr_session(action: serde_json::Value, phase: &str, time_left_ms: f64) -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 2,
+            "benchEnabled": false,
+            "timer": { "phase": phase, "adjustedTimeLeftInPhase": time_left_ms },
+            "myTeam": [
+                { "cellId": 1, "assignedPosition": "top", "championPickIntent": 0 },
+                { "cellId": 2, "assignedPosition": "middle", "championPickIntent": 0 }
+            ],
+            "actions": [[action]]
+        })
+    }
+
+    fn patches(api: &FakeLcu) -> Vec<serde_json::Value> {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == "PATCH")
+            .filter_map(|r| r.body)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ban_hovers_and_locks_when_timer_is_due() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let session = sr_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 0 }),
+            "BAN_PICK",
+            1000.0,
+        );
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(
+            patches(&api),
+            vec![
+                json!({ "championId": 157, "completed": false }),
+                json!({ "championId": 157, "completed": true }),
+            ]
+        );
+        assert!(cs.handled_actions[&7].completed);
+    }
+
+    #[tokio::test]
+    async fn rejected_lock_is_logged_and_retried() {
+        let api = FakeLcu::new();
+        api.script("PATCH", "/lol-champ-select/v1/session/actions/7", [json!({})]);
+        api.script_error("PATCH", "/lol-champ-select/v1/session/actions/7", 500, "RPC_ERROR", "Invalid championId");
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let session = sr_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 0 }),
+            "BAN_PICK",
+            1000.0,
+        );
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        let state = &cs.handled_actions[&7];
+        assert!(!state.completed);
+        assert!(state.lock_scheduled_at.is_some());
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::Log(ref s) if s.contains("RPC_ERROR"))));
+    }
+
+    #[tokio::test]
+    async fn ban_avoids_teammate_intent() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut session = sr_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 0 }),
+            "BAN_PICK",
+            1000.0,
+        );
+        session["myTeam"][0]["championPickIntent"] = json!(157);
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        let bodies = patches(&api);
+        assert!(!bodies.is_empty());
+        assert!(bodies.iter().all(|b| b["championId"] == json!(24)));
+    }
+
+    #[tokio::test]
+    async fn ban_skips_champions_already_banned() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut session = sr_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 157 }),
+            "BAN_PICK",
+            1000.0,
+        );
+        session["actions"][0]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "id": 6, "actorCellId": 1, "type": "ban", "isInProgress": false, "completed": true, "championId": 157 }));
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(
+            patches(&api),
+            vec![
+                json!({ "championId": 24, "completed": false }),
+                json!({ "championId": 24, "completed": true }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ban_skips_teammate_hover_and_session_bans() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut s = settings();
+        s.sr_bans.insert("middle".into(), vec![157, 24, 238]);
+        let mut session = sr_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 0 }),
+            "BAN_PICK",
+            1000.0,
+        );
+        session["actions"][0]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "id": 6, "actorCellId": 1, "type": "ban", "isInProgress": true, "completed": false, "championId": 24 }));
+        session["bans"] = json!({ "myTeamBans": [], "theirTeamBans": [157] });
+
+        handle_sr_pick_ban(&api, &s, &tx, session, &mut cs, &heroes(), 2).await;
+
+        let bodies = patches(&api);
+        assert!(!bodies.is_empty());
+        assert!(bodies.iter().all(|b| b["championId"] == json!(238)));
+    }
+
+    #[tokio::test]
+    async fn pick_waits_for_scheduled_lock() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut s = settings();
+        s.auto_lock = true;
+        let session = sr_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": true, "completed": false, "championId": 0 }),
+            "BAN_PICK",
+            30000.0,
+        );
+
+        handle_sr_pick_ban(&api, &s, &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(patches(&api), vec![json!({ "championId": 103, "completed": false })]);
+        let state = &cs.handled_actions[&9];
+        assert!(state.hovered);
+        assert!(!state.completed);
+        assert!(state.lock_scheduled_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn pick_intent_is_shown_once_before_turn() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let session = sr_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": false, "completed": false, "championId": 0 }),
+            "PLANNING",
+            30000.0,
+        );
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session.clone(), &mut cs, &heroes(), 2).await;
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(patches(&api), vec![json!({ "championId": 103, "completed": false })]);
+    }
+
+    #[tokio::test]
+    async fn pick_falls_back_when_first_choice_is_banned() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut s = settings();
+        s.auto_lock = true;
+        s.lock_time = 60;
+        let mut session = sr_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": true, "completed": false, "championId": 103 }),
+            "BAN_PICK",
+            30000.0,
+        );
+        session["bans"] = json!({ "myTeamBans": [], "theirTeamBans": [103] });
+
+        handle_sr_pick_ban(&api, &s, &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(
+            patches(&api),
+            vec![
+                json!({ "championId": 7, "completed": false }),
+                json!({ "championId": 7, "completed": true }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pick_skips_champions_the_account_cannot_play() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-champ-select/v1/pickable-champion-ids", [json!([7, 157])]);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let session = sr_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": false, "completed": false, "championId": 0 }),
+            "PLANNING",
+            30000.0,
+        );
+
+        handle_sr_pick_ban(&api, &settings(), &tx, session.clone(), &mut cs, &heroes(), 2).await;
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(patches(&api), vec![json!({ "championId": 7, "completed": false })]);
+        let fetches = api
+            .requests()
+            .into_iter()
+            .filter(|r| r.endpoint.ends_with("-champion-ids"))
+            .count();
+        assert_eq!(fetches, 2);
+        assert!(rx.try_iter().any(|m| matches!(
+            m,
+            GuiMsg::ChampionAvailability { pickable: Some(ref p), bannable: None } if p.contains(&7)
+        )));
+    }
+
+    #[tokio::test]
+    async fn failed_availability_fetch_is_retried_with_cap() {
+        let api = FakeLcu::new();
+        let pickable = "/lol-champ-select/v1/pickable-champion-ids";
+        api.script_error("GET", pickable, 500, "RPC_ERROR", "Not ready");
+        api.script("GET", pickable, [json!([7, 157])]);
+        api.script("GET", "/lol-champ-select/v1/bannable-champion-ids", [json!([7])]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let fetches = |api: &FakeLcu, endpoint: &str| api.requests().iter().filter(|r| r.endpoint == endpoint).count();
+
+        load_champion_availability(&api, &tx, &mut cs).await;
+        assert!(!cs.availability_checked && cs.pickable.is_none());
+        assert!(cs.availability_retry_at.is_some());
+
+        load_champion_availability(&api, &tx, &mut cs).await;
+        assert!(cs.availability_checked);
+        assert_eq!(cs.pickable, Some(HashSet::from([7, 157])));
+        assert_eq!(fetches(&api, "/lol-champ-select/v1/bannable-champion-ids"), 1);
+
+        // 一直失败时达到次数上限后不再查询
+        let api = FakeLcu::new();
+        api.script_error("GET", pickable, 500, "RPC_ERROR", "Not ready");
+        let mut cs = ChampSelectState::default();
+        for _ in 0..AVAILABILITY_MAX_ATTEMPTS {
+            load_champion_availability(&api, &tx, &mut cs).await;
+        }
+        assert!(cs.availability_checked && cs.pickable.is_none());
+        assert_eq!(fetches(&api, pickable), AVAILABILITY_MAX_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn empty_availability_list_is_fetched_again() {
+        let api = FakeLcu::new();
+        let pickable = "/lol-champ-select/v1/pickable-champion-ids";
+        api.script("GET", pickable, [json!([]), json!([]), json!([]), json!([7])]);
+        api.script("GET", "/lol-champ-select/v1/bannable-champion-ids", [json!([7])]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+
+        // 空列表不缓存，也不占用失败次数
+        for _ in 0..AVAILABILITY_MAX_ATTEMPTS {
+            load_champion_availability(&api, &tx, &mut cs).await;
+            assert!(!cs.availability_checked && cs.pickable.is_none());
+        }
+        assert_eq!(cs.bannable, Some(HashSet::from([7])));
+        let draft = DraftInfo::from_session(&json!({}), 2, cs.pickable.as_ref(), cs.bannable.as_ref());
+        assert_eq!(draft.first_pick(&[7]), 7);
+
+        load_champion_availability(&api, &tx, &mut cs).await;
+        assert!(cs.availability_checked);
+        assert_eq!(cs.pickable, Some(HashSet::from([7])));
+    }
+
+    #[tokio::test]
+    async fn pick_intent_follows_teammate_changes() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut session = sr_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": false, "completed": false, "championId": 0 }),
+            "PLANNING",
+            30000.0,
+        );
+        session["myTeam"][0]["championPickIntent"] = json!(103);
+        handle_sr_pick_ban(&api, &settings(), &tx, session.clone(), &mut cs, &heroes(), 2).await;
+
+        session["myTeam"][0]["championPickIntent"] = json!(0);
+        session["actions"][0][0]["championId"] = json!(7);
+        cs.handled_actions.get_mut(&9).unwrap().last_act_time = None;
+        handle_sr_pick_ban(&api, &settings(), &tx, session, &mut cs, &heroes(), 2).await;
+
+        assert_eq!(
+            patches(&api),
+            vec![
+                json!({ "championId": 7, "completed": false }),
+                json!({ "championId": 103, "completed": false }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn aram_bench_swaps_highest_priority_target() {
+        let api = FakeLcu::new();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            snipe_list: vec![222, 21],
+            ..Default::default()
+        };
+        let session = json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "benchChampions": [{ "championId": 21 }, { "championId": 222 }],
+            "myTeam": [{ "cellId": 0, "championId": 1 }]
+        });
+
+        handle_aram_bench(&api, &s, &tx, session, &mut ChampSelectState::default(), 0, &heroes()).await;
+
+        let posts: Vec<_> = api.requests().into_iter().filter(|r| r.method == "POST").collect();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].endpoint, "/lol-champ-select/v1/session/bench/swap/222");
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::BenchUpdate(ref ids) if ids == &vec![21, 222])));
+    }
+
+    #[tokio::test]
+    async fn aram_bench_keeps_current_top_choice() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            snipe_list: vec![222, 21],
+            ..Default::default()
+        };
+        let session = json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "benchChampions": [{ "championId": 21 }],
+            "myTeam": [{ "cellId": 0, "championId": 222 }]
+        });
+
+        handle_aram_bench(&api, &s, &tx, session, &mut ChampSelectState::default(), 0, &heroes()).await;
+
+        assert!(api.requests().iter().all(|r| r.method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn champ_select_fetches_session_when_nothing_pushed() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-champ-select/v1/session",
+            [json!({
+                "localPlayerCellId": 0,
+                "benchEnabled": true,
+                "benchChampions": [],
+                "myTeam": [{ "cellId": 0, "championId": 1 }]
+            })],
+        );
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        handle_champ_select(&api, &Settings::default(), None, &tx, None, &mut ChampSelectState::default(), &heroes()).await;
+
+        // 会话本身，以及每次英雄选择只查询一次的重随次数；未开启自动皮肤时不读取皮肤列表
+        let reqs: Vec<(String, String)> = api.take_requests().into_iter().map(|r| (r.method, r.endpoint)).collect();
+        assert_eq!(
+            reqs,
+            [
+                ("GET".to_string(), "/lol-champ-select/v1/session".to_string()),
+                ("GET".to_string(), "/lol-summoner/v1/current-summoner/rerollPoints".to_string()),
+            ]
+        );
+    }
+
+    /// 竞技场会话：自己在 2 号格子，双人队友在 3 号格子，都没有分路
+    fn arena_session(action: serde_json::Value, partner_intent: i32) -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 2,
+            "benchEnabled": false,
+            "timer": { "phase": "BAN_PICK", "adjustedTimeLeftInPhase": 1000.0 },
+            "myTeam": [
+                { "cellId": 2, "assignedPosition": "", "championPickIntent": 0 },
+                { "cellId": 3, "assignedPosition": "", "championPickIntent": partner_intent }
+            ],
+            "actions": [[action]]
+        })
+    }
+
+    #[tokio::test]
+    async fn arena_pick_uses_arena_list_and_avoids_duo_intent() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-gameflow/v1/session", [json!({ "map": { "id": 30, "gameMode": "CHERRY" } })]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let mut s = settings();
+        s.arena_picks = vec![103, 7];
+        let session = arena_session(
+            json!({ "id": 9, "actorCellId": 2, "type": "pick", "isInProgress": false, "completed": false, "championId": 0 }),
+            103,
+        );
+
+        handle_champ_select(&api, &s, None, &tx, Some(&session), &mut cs, &heroes()).await;
+
+        assert!(cs.arena);
+        assert_eq!(patches(&api), vec![json!({ "championId": 7, "completed": false })]);
+    }
+
+    #[tokio::test]
+    async fn arena_is_detected_from_session_and_uses_arena_timing() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let s = Settings {
+            arena_bans: vec![157],
+            arena_auto_lock: true,
+            auto_ban_lock: false,
+            ..Default::default()
+        };
+        let session = arena_session(
+            json!({ "id": 7, "actorCellId": 2, "type": "ban", "isInProgress": true, "completed": false, "championId": 0 }),
+            0,
+        );
+
+        handle_champ_select(&api, &s, None, &tx, Some(&session), &mut cs, &heroes()).await;
+
+        assert!(cs.arena);
+        assert_eq!(
+            patches(&api),
+            vec![
+                json!({ "championId": 157, "completed": false }),
+                json!({ "championId": 157, "completed": true }),
+            ]
+        );
+    }
+
+    fn aram_session(champion_id: i32, bench: &[i32]) -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "benchChampions": bench.iter().map(|c| json!({ "championId": c })).collect::<Vec<_>>(),
+            "myTeam": [{ "cellId": 0, "championId": champion_id }]
+        })
+    }
+
+    fn posts(api: &FakeLcu) -> Vec<String> {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| r.endpoint)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn aram_rerolls_unwanted_champion_once() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-summoner/v1/current-summoner/rerollPoints",
+            [json!({ "numberOfRolls": 2 }), json!({ "numberOfRolls": 1 })],
+        );
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            auto_reroll: true,
+            snipe_list: vec![222],
+            ..Default::default()
+        };
+        let mut cs = ChampSelectState::default();
+
+        handle_aram_bench(&api, &s, &tx, aram_session(1, &[21]), &mut cs, 0, &heroes()).await;
+        handle_aram_bench(&api, &s, &tx, aram_session(1, &[21]), &mut cs, 0, &heroes()).await;
+
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/my-selection/reroll"]);
+        assert_eq!(cs.rerolls_left, Some(1));
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::RerollsLeft(1))));
+    }
+
+    #[tokio::test]
+    async fn aram_keeps_listed_champion_and_prefers_bench() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-summoner/v1/current-summoner/rerollPoints", [json!({ "numberOfRolls": 2 })]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            auto_reroll: true,
+            reroll_keep_list: vec![1],
+            snipe_list: vec![222],
+            ..Default::default()
+        };
+
+        // 当前英雄在保留列表中
+        handle_aram_bench(&api, &s, &tx, aram_session(1, &[21]), &mut ChampSelectState::default(), 0, &heroes()).await;
+        assert!(posts(&api).is_empty());
+
+        // 板凳席上有秒选目标时只交换不重随
+        handle_aram_bench(&api, &s, &tx, aram_session(2, &[222]), &mut ChampSelectState::default(), 0, &heroes()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/bench/swap/222"]);
+    }
+
+    #[tokio::test]
+    async fn aram_does_not_reroll_without_points() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-summoner/v1/current-summoner/rerollPoints", [json!({ "numberOfRolls": 0 })]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            auto_reroll: true,
+            ..Default::default()
+        };
+
+        handle_aram_bench(&api, &s, &tx, aram_session(1, &[]), &mut ChampSelectState::default(), 0, &heroes()).await;
+
+        assert!(posts(&api).is_empty());
+    }
+
+    #[test]
+    fn tier_upgrade_respects_threshold() {
+        let scores = HashMap::from([(1, 2.0), (21, 3.0), (222, 5.0), (99, 1.0)]);
+
+        assert_eq!(tier_upgrade(&scores, &[21, 222], 1, 1.0), Some(222));
+        assert_eq!(tier_upgrade(&scores, &[21], 1, 1.5), None);
+        // 不在评分表中的英雄不会被换入，当前英雄不在表中时按最低分计算
+        assert_eq!(tier_upgrade(&scores, &[157], 1, 0.5), None);
+        assert_eq!(tier_upgrade(&scores, &[21], 157, 2.0), Some(21));
+    }
+
+    #[tokio::test]
+    async fn aram_swaps_to_higher_scored_bench_champion() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            tier_scoring: true,
+            snipe_list: vec![1],
+            ..Default::default()
+        };
+        let mut cs = ChampSelectState {
+            tier_scores: Some(HashMap::from([(1, 2.0), (2, 1.0), (21, 4.0)])),
+            ..Default::default()
+        };
+
+        // 当前英雄在秒选列表中，不按评分交换
+        handle_aram_bench(&api, &s, &tx, aram_session(1, &[21]), &mut cs, 0, &heroes()).await;
+        assert!(posts(&api).is_empty());
+
+        handle_aram_bench(&api, &s, &tx, aram_session(2, &[21]), &mut cs, 0, &heroes()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/bench/swap/21"]);
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::LcuApi;
+use crate::models::{ChampSelectState, GuiMsg, Hero, Settings};
+use crate::utils::{lookup_hero_name_by_id, solo_rank_label};
+
+/// 在英雄选择聊天中发送模板消息
+///
+/// 进入时的问候、分配到位置后的位置消息各发送一次，锁定英雄后发送锁定消息
+/// （换英雄后再次发送）。模板支持 `{role}`、`{champion}`、`{rank}` 占位符，
+/// 为空的模板不发送。聊天房间还没建立时下次再试。
+pub async fn handle_chat(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    cs: &mut ChampSelectState,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) {
+    if !settings.chat_enable {
+        return;
+    }
+    let position = session
+        .get("myTeam")
+        .and_then(|v| v.as_array())
+        .and_then(|tm| {
+            tm.iter()
+                .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(local_cell_id))
+        })
+        .and_then(|m| m.get("assignedPosition"))
+        .and_then(|s| s.as_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let role = role_label(&position);
+    let champion = locked_pick(session, local_cell_id);
+
+    let mut pending: Vec<(String, &str)> = Vec::new();
+    pending.push(("greeting".to_string(), &settings.chat_greeting));
+    if !role.is_empty() {
+        pending.push(("role".to_string(), &settings.chat_role));
+    }
+    if champion > 0 {
+        pending.push((format!("lock-{}", champion), &settings.chat_lock));
+    }
+    pending.retain(|(key, template)| !template.trim().is_empty() && !cs.chat_sent.contains(key));
+    if pending.is_empty() {
+        return;
+    }
+
+    let Some(room) = champ_select_room(api).await else {
+        return;
+    };
+    if cs.chat_rank.is_none() && pending.iter().any(|(_, t)| t.contains("{rank}")) {
+        cs.chat_rank = Some(current_rank(api).await);
+    }
+    let champion_name = if champion > 0 {
+        lookup_hero_name_by_id(shared_heroes, champion)
+    } else {
+        String::new()
+    };
+
+    for (key, template) in pending {
+        let text = render(
+            template,
+            role,
+            &champion_name,
+            cs.chat_rank.as_deref().unwrap_or(""),
+        );
+        cs.chat_sent.insert(key);
+        let body = serde_json::json!({ "body": text, "type": "chat" });
+        match api
+            .request(
+                "POST",
+                &format!("/lol-chat/v1/conversations/{}/messages", room),
+                Some(body),
+            )
+            .await
+        {
+            Ok(_) => tx.send(GuiMsg::Log(format!("发送消息: {}", text))).ok(),
+            Err(e) => tx.send(GuiMsg::Log(format!("发送消息失败: {}", e))).ok(),
+        };
+    }
+}
+
+/// 替换模板中的占位符
+fn render(template: &str, role: &str, champion: &str, rank: &str) -> String {
+    template
+        .replace("{role}", role)
+        .replace("{champion}", champion)
+        .replace("{rank}", rank)
+}
+
+fn role_label(position: &str) -> &'static str {
+    match position {
+        "top" => "上单",
+        "jungle" => "打野",
+        "middle" => "中单",
+        "bottom" => "下路",
+        "utility" => "辅助",
+        _ => "",
+    }
+}
+
+/// 自己已完成的选人 action 中的英雄，没有时返回 0
+fn locked_pick(session: &serde_json::Value, local_cell_id: i64) -> i32 {
+    session
+        .get("actions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|g| g.as_array())
+        .flatten()
+        .find(|a| {
+            a.get("actorCellId").and_then(|v| v.as_i64()) == Some(local_cell_id)
+                && a.get("type").and_then(|v| v.as_str()) == Some("pick")
+                && a.get("completed").and_then(|v| v.as_bool()).unwrap_or(false)
+        })
+        .and_then(|a| a.get("championId"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0) as i32
+}
+
+/// 英雄选择聊天房间的ID
+async fn champ_select_room(api: &impl LcuApi) -> Option<String> {
+    let conversations = api
+        .request("GET", "/lol-chat/v1/conversations", None)
+        .await
+        .ok()?;
+    conversations
+        .as_array()?
+        .iter()
+        .find(|c| c.get("type").and_then(|v| v.as_str()) == Some("championSelect"))
+        .and_then(|c| c.get("id"))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+/// 自己的单双排段位
+async fn current_rank(api: &impl LcuApi) -> String {
+    let stats = api
+        .request("GET", "/lol-ranked/v1/current-ranked-stats", None)
+        .await
+        .unwrap_or_default();
+    solo_rank_label(&stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    const ROOM: &str = "abc@champ-select.pvp.net";
+
+    fn heroes() -> Arc<Mutex<HashMap<i32, Hero>>> {
+        Arc::new(Mutex::new(HashMap::new()))
+    }
+
+    fn api() -> FakeLcu {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-chat/v1/conversations",
+            [json!([
+                { "id": "lobby@sec.pvp.net", "type": "customGame" },
+                { "id": ROOM, "type": "championSelect" }
+            ])],
+        );
+        api
+    }
+
+    fn session(pick_completed: bool) -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 2,
+            "myTeam": [{ "cellId": 2, "assignedPosition": "middle" }],
+            "actions": [[{ "id": 9, "actorCellId": 2, "type": "pick", "completed": pick_completed, "championId": 103 }]]
+        })
+    }
+
+    fn messages(api: &FakeLcu) -> Vec<String> {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .inspect(|r| assert_eq!(r.endpoint, format!("/lol-chat/v1/conversations/{}/messages", ROOM)))
+            .filter_map(|r| r.body?["body"].as_str().map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn render_replaces_placeholders() {
+        assert_eq!(
+            render("{role} {champion} ({rank})", "中单", "阿狸", "荣耀黄金 II"),
+            "中单 阿狸 (荣耀黄金 II)"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_greeting_role_and_lock_once() {
+        let api = api();
+        api.script(
+            "GET",
+            "/lol-ranked/v1/current-ranked-stats",
+            [json!({ "queueMap": { "RANKED_SOLO_5x5": { "tier": "GOLD", "division": "II" } } })],
+        );
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            chat_enable: true,
+            chat_greeting: "hi".into(),
+            chat_role: "{role} {rank}".into(),
+            ..Default::default()
+        };
+        let mut cs = ChampSelectState::default();
+
+        handle_chat(&api, &s, &tx, &session(false), 2, &mut cs, &heroes()).await;
+        handle_chat(&api, &s, &tx, &session(false), 2, &mut cs, &heroes()).await;
+        assert_eq!(messages(&api), vec!["hi", "中单 荣耀黄金 II"]);
+
+        handle_chat(&api, &s, &tx, &session(true), 2, &mut cs, &heroes()).await;
+        handle_chat(&api, &s, &tx, &session(true), 2, &mut cs, &heroes()).await;
+        assert_eq!(messages(&api).len(), 3);
+        assert!(messages(&api)[2].starts_with("锁定 "));
+    }
+
+    #[tokio::test]
+    async fn disabled_chat_sends_nothing() {
+        let api = api();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            chat_greeting: "hi".into(),
+            ..Default::default()
+        };
+
+        handle_chat(&api, &s, &tx, &session(true), 2, &mut ChampSelectState::default(), &heroes()).await;
+
+        assert!(api.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn waits_for_champ_select_room() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-chat/v1/conversations", [json!([]), json!([{ "id": ROOM, "type": "championSelect" }])]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            chat_enable: true,
+            chat_greeting: "hi".into(),
+            chat_role: String::new(),
+            ..Default::default()
+        };
+        let mut cs = ChampSelectState::default();
+
+        handle_chat(&api, &s, &tx, &session(false), 2, &mut cs, &heroes()).await;
+        assert!(messages(&api).is_empty());
+
+        handle_chat(&api, &s, &tx, &session(false), 2, &mut cs, &heroes()).await;
+        assert_eq!(messages(&api), vec!["hi"]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::models::{ChampSelectState, DraftAction, DraftCell, DraftSnapshot};
+
+/// 根据会话和助手的处理状态生成英雄选择快照
+///
+/// 有已完成选人 action 的格子视为已锁定；大乱斗没有选人 action，
+/// 当前英雄即视为已锁定。
+pub fn draft_snapshot(
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    cs: &ChampSelectState,
+) -> DraftSnapshot {
+    let bench_enabled = session.get("benchEnabled").and_then(|v| v.as_bool()).unwrap_or(false);
+    let actions: Vec<&serde_json::Value> = session
+        .get("actions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|g| g.as_array())
+        .flatten()
+        .collect();
+
+    // 格子 -> 已锁定的英雄
+    let mut locked: HashMap<i64, i32> = HashMap::new();
+    for a in &actions {
+        let completed = a.get("completed").and_then(|v| v.as_bool()).unwrap_or(false);
+        if completed && a.get("type").and_then(|v| v.as_str()) == Some("pick") {
+            let actor = a.get("actorCellId").and_then(|v| v.as_i64()).unwrap_or(-1);
+            let champ = a.get("championId").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            locked.insert(actor, champ);
+        }
+    }
+
+    let team = |key: &str| -> Vec<DraftCell> {
+        let members = session.get(key).and_then(|v| v.as_array());
+        members
+            .into_iter()
+            .flatten()
+            .map(|m| {
+                let cell_id = m.get("cellId").and_then(|v| v.as_i64()).unwrap_or(-1);
+                let champion = m.get("championId").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                let intent =
+                    m.get("championPickIntent").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                let locked = match locked.get(&cell_id) {
+                    Some(&c) if c > 0 => c,
+                    Some(_) => champion,
+                    None if bench_enabled => champion,
+                    None => 0,
+                };
+                DraftCell {
+                    cell_id,
+                    position: m
+                        .get("assignedPosition")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or("")
+                        .to_lowercase(),
+                    hovered: if locked > 0 {
+                        0
+                    } else if champion > 0 {
+                        champion
+                    } else {
+                        intent
+                    },
+                    locked,
+                    is_local: cell_id == local_cell_id,
+                }
+            })
+            .collect()
+    };
+
+    let bans = |key: &str| -> Vec<i32> {
+        session
+            .get("bans")
+            .and_then(|b| b.get(key))
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|x| x.as_i64()).map(|x| x as i32).collect())
+            .unwrap_or_default()
+    };
+
+    // 自己未完成的 action，优先取正在进行的
+    let mine: Vec<&&serde_json::Value> = actions
+        .iter()
+        .filter(|a| {
+            a.get("actorCellId").and_then(|v| v.as_i64()) == Some(local_cell_id)
+                && !a.get("completed").and_then(|v| v.as_bool()).unwrap_or(false)
+        })
+        .collect();
+    let in_progress =
+        |a: &serde_json::Value| a.get("isInProgress").and_then(|v| v.as_bool()).unwrap_or(false);
+    let pending = mine
+        .iter()
+        .find(|a| in_progress(a))
+        .or_else(|| mine.first())
+        .map(|a| {
+            let id = a.get("id").and_then(|v| v.as_i64()).unwrap_or(-1);
+            let state = cs.handled_actions.get(&id);
+            DraftAction {
+                kind: a.get("type").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                in_progress: in_progress(a),
+                target: state.map_or(0, |s| s.target),
+                lock_at: state.and_then(|s| s.lock_scheduled_at),
+            }
+        });
+
+    let timer = session.get("timer");
+    let infinite = timer
+        .and_then(|t| t.get("isInfinite"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let phase_ends_at = timer
+        .and_then(|t| t.get("adjustedTimeLeftInPhase"))
+        .and_then(|v| v.as_f64())
+        .filter(|&ms| ms >= 0.0 && !infinite)
+        .map(|ms| Instant::now() + Duration::from_millis(ms as u64));
+
+    DraftSnapshot {
+        my_team: team("myTeam"),
+        their_team: team("theirTeam"),
+        my_bans: bans("myTeamBans"),
+        their_bans: bans("theirTeamBans"),
+        pending,
+        phase: timer
+            .and_then(|t| t.get("phase"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        phase_ends_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ActionState;
+    use serde_json::json;
+
+    fn session() -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 1,
+            "benchEnabled": false,
+            "myTeam": [
+                { "cellId": 0, "assignedPosition": "TOP", "championId": 86, "championPickIntent": 0 },
+                { "cellId": 1, "assignedPosition": "middle", "championId": 0, "championPickIntent": 103 }
+            ],
+            "theirTeam": [{ "cellId": 5, "championId": 0, "championPickIntent": 0 }],
+            "actions": [
+                [{ "id": 1, "actorCellId": 0, "type": "pick", "championId": 86, "completed": true }],
+                [{ "id": 2, "actorCellId": 1, "type": "pick", "championId": 0, "completed": false, "isInProgress": true }]
+            ],
+            "bans": { "myTeamBans": [157], "theirTeamBans": [64] },
+            "timer": { "phase": "BAN_PICK", "adjustedTimeLeftInPhase": 20000, "isInfinite": false }
+        })
+    }
+
+    #[test]
+    fn snapshot_separates_locked_and_hovered_champions() {
+        let snap = draft_snapshot(&session(), 1, &ChampSelectState::default());
+
+        assert_eq!(snap.my_team[0].locked, 86);
+        assert_eq!(snap.my_team[0].position, "top");
+        assert_eq!((snap.my_team[1].hovered, snap.my_team[1].locked), (103, 0));
+        assert!(snap.my_team[1].is_local);
+        assert_eq!(snap.their_team.len(), 1);
+        assert_eq!((snap.my_bans, snap.their_bans), (vec![157], vec![64]));
+        assert_eq!(snap.phase, "BAN_PICK");
+        assert!(snap.phase_ends_at.is_some_and(|t| t > Instant::now() + Duration::from_secs(15)));
+    }
+
+    #[test]
+    fn pending_action_carries_scheduled_lock() {
+        let lock_at = Instant::now() + Duration::from_secs(5);
+        let mut cs = ChampSelectState::default();
+        cs.handled_actions.insert(
+            2,
+            ActionState {
+                hovered: true,
+                target: 103,
+                lock_scheduled_at: Some(lock_at),
+                ..Default::default()
+            },
+        );
+
+        let pending = draft_snapshot(&session(), 1, &cs).pending.unwrap();
+
+        assert_eq!(pending.kind, "pick");
+        assert!(pending.in_progress);
+        assert_eq!((pending.target, pending.lock_at), (103, Some(lock_at)));
+    }
+}
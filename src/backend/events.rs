@@ -0,0 +1,172 @@
+use futures_util::{SinkExt, StreamExt};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+use crate::models::LcuConnection;
+
+/// 订阅的 LCU WAMP 事件
+const SUBSCRIPTIONS: [&str; 5] = [
+    "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase",
+    "OnJsonApiEvent_lol-champ-select_v1_session",
+    "OnJsonApiEvent_lol-lobby_v2_lobby",
+    "OnJsonApiEvent_lol-matchmaking_v1_ready-check",
+    "OnJsonApiEvent_lol-matchmaking_v1_search",
+];
+
+/// WAMP 操作码
+const WAMP_SUBSCRIBE: u64 = 5;
+const WAMP_EVENT: u64 = 8;
+
+/// 客户端推送的事件
+pub enum LcuEvent {
+    /// 游戏流程阶段变化
+    Phase(String),
+    /// 英雄选择会话更新（会话结束时为 None）
+    ChampSelect(Option<serde_json::Value>),
+    /// 房间信息变化
+    Lobby,
+    /// 匹配/准备确认状态变化
+    Matchmaking,
+}
+
+/// 建立 WebSocket 连接并在后台转发事件
+///
+/// 连接失败或断开时发送端会被丢弃，接收端的 `recv()` 返回 None，
+/// 调用方据此回退到轮询模式。
+pub fn spawn_event_listener(conn: &LcuConnection) -> mpsc::UnboundedReceiver<LcuEvent> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let conn = conn.clone();
+    tokio::spawn(async move {
+        let _ = listen(conn, tx).await;
+    });
+    rx
+}
+
+async fn listen(conn: LcuConnection, tx: mpsc::UnboundedSender<LcuEvent>) -> anyhow::Result<()> {
+    let ws_url = conn.url.replacen("https://", "wss://", 1);
+    let mut request = ws_url.into_client_request()?;
+    request
+        .headers_mut()
+        .insert("Authorization", conn.auth_header.parse()?);
+
+    let (mut ws, _) = tokio_tungstenite::connect_async_tls_with_config(
+        request,
+        None,
+        true,
+        Some(Connector::Rustls(Arc::new(insecure_tls_config()))),
+    )
+    .await?;
+
+    for topic in SUBSCRIPTIONS {
+        let msg = serde_json::json!([WAMP_SUBSCRIBE, topic]).to_string();
+        ws.send(Message::Text(msg)).await?;
+    }
+
+    while let Some(msg) = ws.next().await {
+        let text = match msg? {
+            Message::Text(t) => t,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        if let Some(ev) = parse_event(&text) {
+            if tx.send(ev).is_err() {
+                break; // 后端已不再监听
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解析 `[8, "OnJsonApiEvent_...", { data, eventType, uri }]` 格式的消息
+fn parse_event(text: &str) -> Option<LcuEvent> {
+    let v: serde_json::Value = serde_json::from_str(text).ok()?;
+    let arr = v.as_array()?;
+    if arr.first()?.as_u64()? != WAMP_EVENT {
+        return None;
+    }
+    let payload = arr.get(2)?;
+    let uri = payload.get("uri")?.as_str()?;
+    let event_type = payload.get("eventType").and_then(|e| e.as_str()).unwrap_or("");
+    let data = payload.get("data").cloned().unwrap_or(serde_json::Value::Null);
+
+    if uri == "/lol-gameflow/v1/gameflow-phase" {
+        data.as_str().map(|p| LcuEvent::Phase(p.to_string()))
+    } else if uri == "/lol-champ-select/v1/session" {
+        if event_type == "Delete" {
+            Some(LcuEvent::ChampSelect(None))
+        } else {
+            Some(LcuEvent::ChampSelect(Some(data)))
+        }
+    } else if uri.starts_with("/lol-lobby/v2/lobby") {
+        Some(LcuEvent::Lobby)
+    } else if uri.starts_with("/lol-matchmaking/v1/") {
+        Some(LcuEvent::Matchmaking)
+    } else {
+        None
+    }
+}
+
+/// 客户端使用自签名证书，与 HTTP 请求一样跳过证书校验
+fn insecure_tls_config() -> rustls::ClientConfig {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("ring provider supports default protocol versions")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+        .with_no_client_auth()
+}
+
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<rustls::crypto::CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
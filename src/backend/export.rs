@@ -0,0 +1,307 @@
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::LcuApi;
+use crate::models::{GameRecord, Hero};
+use crate::utils::lookup_hero_name_by_id;
+
+/// 结算数据导出的统计项（CSV 列名为小写）
+const EOG_STATS: [&str; 11] = [
+    "CHAMPIONS_KILLED",
+    "NUM_DEATHS",
+    "ASSISTS",
+    "LEVEL",
+    "MINIONS_KILLED",
+    "NEUTRAL_MINIONS_KILLED",
+    "GOLD_EARNED",
+    "TOTAL_DAMAGE_DEALT_TO_CHAMPIONS",
+    "TOTAL_DAMAGE_TAKEN",
+    "VISION_SCORE",
+    "WARD_PLACED",
+];
+
+const HISTORY_COLUMNS: [&str; 14] = [
+    "game_id",
+    "time",
+    "player",
+    "queue_id",
+    "champion_id",
+    "champion",
+    "kills",
+    "deaths",
+    "assists",
+    "win",
+    "duration",
+    "lp_change",
+    "teammates",
+    "teammate_champions",
+];
+
+/// 导出上一局的结算数据：原始 JSON 以及每名玩家一行的 CSV
+pub async fn export_last_game(
+    api: &impl LcuApi,
+    dir: &Path,
+    heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let eog = api
+        .request("GET", "/lol-end-of-game/v1/eog-stats-block", None)
+        .await
+        .context("没有可导出的结算数据")?;
+    let game_id = eog["gameId"]
+        .as_i64()
+        .filter(|&id| id > 0)
+        .context("没有可导出的结算数据")?;
+    write_pair(
+        dir,
+        &format!("game-{}", game_id),
+        &eog_csv(&eog, heroes),
+        &serde_json::to_string_pretty(&eog)?,
+    )
+}
+
+/// 导出本地战绩中日期范围内（含首尾两天，按本地时间）的对局
+pub fn export_history(
+    records: &[GameRecord],
+    from: NaiveDate,
+    to: NaiveDate,
+    dir: &Path,
+    heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> anyhow::Result<(PathBuf, PathBuf, usize)> {
+    let mut selected: Vec<&GameRecord> = records
+        .iter()
+        .filter(|r| {
+            DateTime::parse_from_rfc3339(&r.time)
+                .map(|t| t.with_timezone(&Local).date_naive())
+                .is_ok_and(|d| d >= from && d <= to)
+        })
+        .collect();
+    anyhow::ensure!(!selected.is_empty(), "{} 至 {} 没有对局记录", from, to);
+    selected.sort_by(|a, b| a.time.cmp(&b.time));
+
+    let (csv, json) = write_pair(
+        dir,
+        &format!("history-{}-{}", from.format("%Y%m%d"), to.format("%Y%m%d")),
+        &history_csv(&selected, heroes),
+        &serde_json::to_string_pretty(&selected)?,
+    )?;
+    Ok((csv, json, selected.len()))
+}
+
+/// 写入同名的 CSV 与 JSON 文件
+fn write_pair(
+    dir: &Path,
+    stem: &str,
+    csv: &str,
+    json: &str,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    fs::create_dir_all(dir).with_context(|| format!("无法创建 {}", dir.display()))?;
+    let csv_path = dir.join(format!("{}.csv", stem));
+    let json_path = dir.join(format!("{}.json", stem));
+    // 带 BOM，Excel 打开中文不乱码
+    fs::write(&csv_path, format!("\u{feff}{}", csv))?;
+    fs::write(&json_path, json)?;
+    Ok((csv_path, json_path))
+}
+
+/// 结算数据 CSV：双方每名玩家一行
+fn eog_csv(eog: &serde_json::Value, heroes: &Arc<Mutex<HashMap<i32, Hero>>>) -> String {
+    let mut header = vec![
+        "game_id".to_string(),
+        "game_length".to_string(),
+        "team".to_string(),
+        "win".to_string(),
+        "player".to_string(),
+        "champion_id".to_string(),
+        "champion".to_string(),
+    ];
+    header.extend(EOG_STATS.iter().map(|s| s.to_lowercase()));
+    let mut lines = vec![csv_row(&header)];
+
+    for team in eog["teams"].as_array().into_iter().flatten() {
+        let side = if team["isPlayerTeam"].as_bool().unwrap_or(false) { "ally" } else { "enemy" };
+        let win = team["isWinningTeam"].as_bool().unwrap_or(false);
+        for p in team["players"].as_array().into_iter().flatten() {
+            let champion_id = p["championId"].as_i64().unwrap_or(0) as i32;
+            let name = match (p["riotIdGameName"].as_str(), p["riotIdTagLine"].as_str()) {
+                (Some(n), Some(t)) if !n.is_empty() && !t.is_empty() => format!("{}#{}", n, t),
+                (Some(n), _) if !n.is_empty() => n.to_string(),
+                _ => p["summonerName"].as_str().unwrap_or("").to_string(),
+            };
+            let mut row = vec![
+                eog["gameId"].to_string(),
+                eog["gameLength"].as_i64().unwrap_or(0).to_string(),
+                side.to_string(),
+                win.to_string(),
+                name,
+                champion_id.to_string(),
+                lookup_hero_name_by_id(heroes, champion_id),
+            ];
+            row.extend(
+                EOG_STATS
+                    .iter()
+                    .map(|s| p["stats"][*s].as_i64().unwrap_or(0).to_string()),
+            );
+            lines.push(csv_row(&row));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+fn history_csv(records: &[&GameRecord], heroes: &Arc<Mutex<HashMap<i32, Hero>>>) -> String {
+    let mut lines = vec![csv_row(&HISTORY_COLUMNS.map(String::from))];
+    for r in records {
+        let teammates: Vec<&str> = r.teammates.iter().map(|t| t.name.as_str()).collect();
+        let teammate_champions: Vec<String> = r
+            .teammates
+            .iter()
+            .map(|t| lookup_hero_name_by_id(heroes, t.champion_id))
+            .collect();
+        lines.push(csv_row(&[
+            r.game_id.to_string(),
+            r.time.clone(),
+            r.player.clone(),
+            r.queue_id.map(|q| q.to_string()).unwrap_or_default(),
+            r.champion_id.to_string(),
+            lookup_hero_name_by_id(heroes, r.champion_id),
+            r.kills.to_string(),
+            r.deaths.to_string(),
+            r.assists.to_string(),
+            r.win.to_string(),
+            r.duration.to_string(),
+            r.lp_change.map(|lp| lp.to_string()).unwrap_or_default(),
+            teammates.join(";"),
+            teammate_champions.join(";"),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// 一行 CSV，含逗号、引号或换行的字段加引号
+fn csv_row(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use crate::models::GameTeammate;
+    use serde_json::json;
+
+    fn heroes() -> Arc<Mutex<HashMap<i32, Hero>>> {
+        Arc::new(Mutex::new(HashMap::new()))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hextech-export-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record(game_id: i64, time: &str) -> GameRecord {
+        GameRecord {
+            game_id,
+            time: time.to_string(),
+            player: "Me#1".into(),
+            queue_id: Some(420),
+            champion_id: 103,
+            kills: 7,
+            deaths: 2,
+            assists: 9,
+            win: true,
+            duration: 1805,
+            lp_change: Some(21),
+            teammates: vec![
+                GameTeammate { name: "A, B".into(), champion_id: 64 },
+                GameTeammate { name: "C".into(), champion_id: 7 },
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_quotes_special_fields() {
+        let row = csv_row(&["a".into(), "b,c".into(), "say \"hi\"".into()]);
+        assert_eq!(row, "a,\"b,c\",\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn history_export_filters_by_date_range() {
+        let dir = temp_dir("history");
+        let records = vec![
+            record(3, "2026-03-05T20:00:00+08:00"),
+            record(2, "2026-03-02T20:00:00+08:00"),
+            record(1, "2026-02-20T20:00:00+08:00"),
+        ];
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+
+        let (csv, json, count) = export_history(&records, day(1), day(4), &dir, &heroes()).unwrap();
+
+        assert_eq!(count, 1);
+        let csv = fs::read_to_string(csv).unwrap();
+        let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').lines().collect();
+        assert_eq!(lines[0], HISTORY_COLUMNS.join(","));
+        assert!(lines[1].starts_with("2,2026-03-02T20:00:00+08:00,Me#1,420,103,"));
+        assert!(lines[1].contains(",\"A, B;C\","));
+        let json: Vec<GameRecord> = serde_json::from_str(&fs::read_to_string(json).unwrap()).unwrap();
+        assert_eq!(json[0].game_id, 2);
+        assert!(export_history(&records, day(10), day(12), &dir, &heroes()).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn last_game_export_has_one_row_per_player() {
+        let dir = temp_dir("eog");
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-end-of-game/v1/eog-stats-block",
+            [json!({
+                "gameId": 42,
+                "gameLength": 1805,
+                "teams": [
+                    { "isPlayerTeam": true, "isWinningTeam": true, "players": [
+                        { "riotIdGameName": "Me", "riotIdTagLine": "1", "championId": 103,
+                          "stats": { "CHAMPIONS_KILLED": 7, "GOLD_EARNED": 12000 } }
+                    ] },
+                    { "isPlayerTeam": false, "isWinningTeam": false, "players": [
+                        { "summonerName": "Foe", "championId": 64, "stats": {} }
+                    ] }
+                ]
+            })],
+        );
+
+        let (csv, json) = export_last_game(&api, &dir, &heroes()).await.unwrap();
+
+        assert!(csv.ends_with("game-42.csv"));
+        let csv = fs::read_to_string(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("42,1805,ally,true,Me#1,103,,7,0,0,0,0,0,12000,"));
+        assert!(lines[2].starts_with("42,1805,enemy,false,Foe,64,"));
+        let raw: serde_json::Value = serde_json::from_str(&fs::read_to_string(json).unwrap()).unwrap();
+        assert_eq!(raw["gameId"], 42);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn last_game_export_fails_without_stats() {
+        let api = FakeLcu::new();
+        api.script_error("GET", "/lol-end-of-game/v1/eog-stats-block", 404, "", "Not found");
+
+        assert!(export_last_game(&api, &temp_dir("none"), &heroes()).await.is_err());
+    }
+}
@@ -1,62 +1,56 @@
-use reqwest::Client;
 use std::time::{Duration, Instant};
 
-use crate::lcu::lcu_request;
-use crate::models::{GuiMsg, LcuConnection, Settings};
+use crate::lcu::LcuApi;
+use crate::models::{GuiMsg, Settings};
 
+use super::honor::handle_honor;
+
+/// 自动接受对局，同一错误只提示一次（就绪检查过期后会一直返回同样的错误）
 pub async fn handle_ready_check(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    last_error: &mut Option<String>,
 ) {
     if settings.auto_accept {
-        let _ = lcu_request(
-            client,
-            conn,
-            "POST",
-            "/lol-matchmaking/v1/ready-check/accept",
-            None,
-        )
-        .await;
+        match api
+            .request("POST", "/lol-matchmaking/v1/ready-check/accept", None)
+            .await
+        {
+            Ok(_) => *last_error = None,
+            Err(e) => {
+                let msg = format!("接受对局失败: {}", e);
+                if last_error.as_deref() != Some(msg.as_str()) {
+                    tx.send(GuiMsg::Log(msg.clone())).unwrap();
+                    *last_error = Some(msg);
+                }
+            }
+        }
     }
 }
 
 pub async fn handle_end_of_game(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     honored: &mut bool,
     played_again: &mut bool,
 ) {
     if settings.auto_honor && !*honored {
-        if let Ok(ballot) =
-            lcu_request(client, conn, "GET", "/lol-honor-v2/v1/ballot", None).await
-        {
-            if let Some(gid) = ballot.get("gameId") {
-                let _ = lcu_request(
-                    client,
-                    conn,
-                    "POST",
-                    "/lol-honor-v2/v1/honor-player",
-                    Some(serde_json::json!({"gameId": gid, "honorCategory": "OPT_OUT"})),
-                )
-                .await;
-                *honored = true;
-                tx.send(GuiMsg::Log("跳过点赞".into())).unwrap();
-            }
-        }
+        handle_honor(api, settings, tx, honored).await;
     }
     if settings.play_again && !*played_again {
-        let _ = lcu_request(client, conn, "POST", "/lol-lobby/v2/play-again", None).await;
+        let result = api.request("POST", "/lol-lobby/v2/play-again", None).await;
         *played_again = true;
-        tx.send(GuiMsg::Log("返回房间".into())).unwrap();
+        match result {
+            Ok(_) => tx.send(GuiMsg::Log("返回房间".into())).unwrap(),
+            Err(e) => tx.send(GuiMsg::Log(format!("返回房间失败: {}", e))).unwrap(),
+        }
     }
 }
 
 pub async fn handle_lobby(
-    client: &Client,
-    conn: &LcuConnection,
+    api: &impl LcuApi,
     settings: &Settings,
     tx: &crossbeam_channel::Sender<GuiMsg>,
     queue_timer: &mut Option<Instant>,
@@ -69,9 +63,7 @@ pub async fn handle_lobby(
         }
         if let Some(t) = queue_timer {
             if t.elapsed().as_secs() >= settings.queue_delay {
-                if let Ok(st) = lcu_request(
-                    client,
-                    conn,
+                if let Ok(st) = api.request(
                     "GET",
                     "/lol-lobby/v2/lobby/matchmaking/search-state",
                     None,
@@ -79,15 +71,16 @@ pub async fn handle_lobby(
                 .await
                 {
                     if st.get("searchState").and_then(|s| s.as_str()).unwrap_or("") != "Searching" {
-                        let _ = lcu_request(
-                            client,
-                            conn,
+                        let result = api.request(
                             "POST",
                             "/lol-lobby/v2/lobby/matchmaking/search",
                             None,
                         )
                         .await;
-                        tx.send(GuiMsg::Log("开始匹配".into())).unwrap();
+                        match result {
+                            Ok(_) => tx.send(GuiMsg::Log("开始匹配".into())).unwrap(),
+                            Err(e) => tx.send(GuiMsg::Log(format!("开始匹配失败: {}", e))).unwrap(),
+                        }
                     }
                 }
                 *queue_timer = Some(Instant::now() + Duration::from_secs(9999));
@@ -95,3 +88,30 @@ pub async fn handle_lobby(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+
+    #[tokio::test]
+    async fn repeated_ready_check_error_is_logged_once() {
+        let api = FakeLcu::new();
+        let accept = "/lol-matchmaking/v1/ready-check/accept";
+        api.script_error("POST", accept, 404, "RPC_ERROR", "No ready check");
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut last_error = None;
+
+        for _ in 0..5 {
+            handle_ready_check(&api, &Settings::default(), &tx, &mut last_error).await;
+        }
+        assert_eq!(rx.try_iter().count(), 1);
+
+        api.script_error("POST", accept, 500, "RPC_ERROR", "Timed out");
+        handle_ready_check(&api, &Settings::default(), &tx, &mut last_error).await;
+        handle_ready_check(&api, &Settings::default(), &tx, &mut last_error).await;
+        let logs: Vec<_> = rx.try_iter().collect();
+        assert_eq!(logs.len(), 1);
+        assert!(matches!(&logs[0], GuiMsg::Log(s) if s.contains("HTTP 500")));
+    }
+}
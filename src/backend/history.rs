@@ -0,0 +1,263 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Local;
+
+use crate::lcu::LcuApi;
+use crate::models::{GameRecord, GameTeammate, GuiMsg};
+use crate::utils::history_path;
+
+/// 把刚结束的对局写入本地战绩
+///
+/// 优先使用结算数据，取不到时使用战绩中与当前对局ID相同的最近一局。
+/// 两者都取不到时下次再试；同一局只记录一次。
+pub async fn record_game(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    queue_id: Option<i64>,
+    recorded: &mut bool,
+) {
+    if *recorded {
+        return;
+    }
+    let Ok(me) = api.request("GET", "/lol-summoner/v1/current-summoner", None).await else {
+        return;
+    };
+    let puuid = me["puuid"].as_str().unwrap_or("");
+    if puuid.is_empty() {
+        return;
+    }
+
+    let eog = api
+        .request("GET", "/lol-end-of-game/v1/eog-stats-block", None)
+        .await
+        .ok()
+        .and_then(|eog| parse_eog_stats(&eog, puuid));
+    let record = match eog {
+        Some(r) => Some(r),
+        None => latest_match(api, puuid).await,
+    };
+    let Some(mut record) = record else {
+        return;
+    };
+    *recorded = true;
+
+    record.time = Local::now().to_rfc3339();
+    record.player = player_name(&me);
+    record.queue_id = record.queue_id.or(queue_id);
+    record.lp_change = lp_change(api, record.game_id).await;
+
+    let path = history_path(puuid);
+    match append_record(&path, &record) {
+        Ok(true) => {
+            tx.send(GuiMsg::Log(format!(
+                "已记录对局: {}/{}/{} {}",
+                record.kills,
+                record.deaths,
+                record.assists,
+                if record.win { "胜利" } else { "失败" }
+            )))
+            .ok();
+            tx.send(GuiMsg::GameRecorded(record)).ok();
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("保存对局记录失败: {}", e))).ok();
+        }
+    }
+}
+
+/// 追加一条记录，已存在相同对局ID时跳过并返回 false
+fn append_record(path: &Path, record: &GameRecord) -> std::io::Result<bool> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let duplicate = existing
+        .lines()
+        .filter_map(|l| serde_json::from_str::<GameRecord>(l).ok())
+        .any(|r| r.game_id == record.game_id);
+    if duplicate {
+        return Ok(false);
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(true)
+}
+
+/// 解析结算数据
+fn parse_eog_stats(eog: &serde_json::Value, puuid: &str) -> Option<GameRecord> {
+    let game_id = eog["gameId"].as_i64().filter(|&id| id > 0)?;
+    let local = &eog["localPlayer"];
+    let stats = &local["stats"];
+    let teams = eog["teams"].as_array();
+    let my_team = teams
+        .into_iter()
+        .flatten()
+        .find(|t| t["isPlayerTeam"].as_bool().unwrap_or(false));
+    let win = match my_team.and_then(|t| t["isWinningTeam"].as_bool()) {
+        Some(w) => w,
+        None => stats["WIN"].as_i64().unwrap_or(0) > 0,
+    };
+    let teammates = my_team
+        .and_then(|t| t["players"].as_array())
+        .into_iter()
+        .flatten()
+        .filter(|p| p["puuid"].as_str() != Some(puuid))
+        .map(|p| GameTeammate {
+            name: player_name(p),
+            champion_id: p["championId"].as_i64().unwrap_or(0) as i32,
+        })
+        .collect();
+    Some(GameRecord {
+        game_id,
+        queue_id: eog["queueId"].as_i64(),
+        champion_id: local["championId"].as_i64().unwrap_or(0) as i32,
+        kills: stats["CHAMPIONS_KILLED"].as_i64().unwrap_or(0),
+        deaths: stats["NUM_DEATHS"].as_i64().unwrap_or(0),
+        assists: stats["ASSISTS"].as_i64().unwrap_or(0),
+        win,
+        duration: eog["gameLength"].as_i64().unwrap_or(0),
+        teammates,
+        ..Default::default()
+    })
+}
+
+/// 战绩中最近的一局，仅当它就是当前对局时返回
+async fn latest_match(api: &impl LcuApi, puuid: &str) -> Option<GameRecord> {
+    let flow = api.request("GET", "/lol-gameflow/v1/session", None).await.ok()?;
+    let game_id = flow["gameData"]["gameId"].as_i64().filter(|&id| id > 0)?;
+    let endpoint = format!(
+        "/lol-match-history/v1/products/lol/{}/matches?begIndex=0&endIndex=1",
+        puuid
+    );
+    let history = api.request("GET", &endpoint, None).await.ok()?;
+    let game = history["games"]["games"]
+        .as_array()?
+        .iter()
+        .find(|g| g["gameId"].as_i64() == Some(game_id))?;
+    let participant = game["participants"].as_array()?.first()?;
+    let stats = &participant["stats"];
+    Some(GameRecord {
+        game_id,
+        queue_id: game["queueId"].as_i64(),
+        champion_id: participant["championId"].as_i64().unwrap_or(0) as i32,
+        kills: stats["kills"].as_i64().unwrap_or(0),
+        deaths: stats["deaths"].as_i64().unwrap_or(0),
+        assists: stats["assists"].as_i64().unwrap_or(0),
+        win: stats["win"].as_bool().unwrap_or(false),
+        duration: game["gameDuration"].as_i64().unwrap_or(0),
+        ..Default::default()
+    })
+}
+
+/// 本局的排位分变化
+async fn lp_change(api: &impl LcuApi, game_id: i64) -> Option<i64> {
+    let note = api
+        .request("GET", "/lol-ranked/v1/current-lp-change-notification", None)
+        .await
+        .ok()?;
+    if note["gameId"].as_i64() != Some(game_id) {
+        return None;
+    }
+    note["leaguePointsDelta"].as_i64()
+}
+
+/// "名称#标签"，没有 Riot ID 时使用召唤师名
+fn player_name(p: &serde_json::Value) -> String {
+    let name = p["gameName"].as_str().or(p["riotIdGameName"].as_str()).unwrap_or("");
+    let tag = p["tagLine"].as_str().or(p["riotIdTagLine"].as_str()).unwrap_or("");
+    match (name, tag) {
+        ("", _) => p["summonerName"]
+            .as_str()
+            .or(p["displayName"].as_str())
+            .unwrap_or("")
+            .to_string(),
+        (n, "") => n.to_string(),
+        (n, t) => format!("{}#{}", n, t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use crate::utils::load_game_history;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn eog() -> serde_json::Value {
+        json!({
+            "gameId": 42,
+            "gameLength": 1805,
+            "localPlayer": {
+                "championId": 103,
+                "puuid": "me",
+                "stats": { "CHAMPIONS_KILLED": 7, "NUM_DEATHS": 2, "ASSISTS": 9 }
+            },
+            "teams": [
+                { "isPlayerTeam": false, "isWinningTeam": false, "players": [] },
+                { "isPlayerTeam": true, "isWinningTeam": true, "players": [
+                    { "puuid": "me", "championId": 103, "riotIdGameName": "Me", "riotIdTagLine": "1" },
+                    { "puuid": "ally", "championId": 64, "riotIdGameName": "Ally", "riotIdTagLine": "CN1" }
+                ] }
+            ]
+        })
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hextech-{}-{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn parses_eog_stats_block() {
+        let record = parse_eog_stats(&eog(), "me").unwrap();
+
+        assert_eq!(record.game_id, 42);
+        assert_eq!((record.champion_id, record.kills, record.deaths, record.assists), (103, 7, 2, 9));
+        assert!(record.win);
+        assert_eq!(record.duration, 1805);
+        assert_eq!(
+            record.teammates,
+            vec![GameTeammate { name: "Ally#CN1".into(), champion_id: 64 }]
+        );
+    }
+
+    #[test]
+    fn append_skips_duplicate_game_ids() {
+        let path = temp_path("dedupe");
+        let record = parse_eog_stats(&eog(), "me").unwrap();
+
+        assert!(append_record(&path, &record).unwrap());
+        assert!(!append_record(&path, &record).unwrap());
+        let second = GameRecord { game_id: 43, ..record.clone() };
+        assert!(append_record(&path, &second).unwrap());
+
+        let ids: Vec<i64> = load_game_history(&path).iter().map(|r| r.game_id).collect();
+        assert_eq!(ids, vec![43, 42]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_match_history_for_current_game() {
+        let api = FakeLcu::new();
+        api.script_error("GET", "/lol-end-of-game/v1/eog-stats-block", 404, "", "Not found");
+        api.script("GET", "/lol-gameflow/v1/session", [json!({ "gameData": { "gameId": 42 } })]);
+        api.script(
+            "GET",
+            "/lol-match-history/v1/products/lol/me/matches?begIndex=0&endIndex=1",
+            [json!({ "games": { "games": [{
+                "gameId": 42, "queueId": 420, "gameDuration": 1500,
+                "participants": [{ "championId": 7, "stats": { "kills": 1, "deaths": 5, "assists": 3, "win": false } }]
+            }] } })],
+        );
+
+        let record = latest_match(&api, "me").await.unwrap();
+
+        assert_eq!((record.game_id, record.queue_id, record.champion_id), (42, Some(420), 7));
+        assert_eq!((record.kills, record.deaths, record.assists, record.win), (1, 5, 3, false));
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use crate::lcu::LcuApi;
+use crate::models::{GuiMsg, HonorRule, Settings};
+
+/// 可以点赞的队友
+#[derive(Debug, Default)]
+struct Ally {
+    puuid: String,
+    summoner_id: i64,
+    /// 名称#标签，没有 Riot ID 时为召唤师名
+    name: String,
+    /// (击杀 + 助攻) / max(死亡, 1)，没有结算数据时为 None
+    kda: Option<f64>,
+    damage: Option<i64>,
+}
+
+/// 按设置的规则点赞队友，找不到合适的队友或选择跳过时提交跳过
+///
+/// 候选人来自点赞投票，KDA 与伤害取自结算数据，开黑好友以好友列表判断。
+pub async fn handle_honor(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    honored: &mut bool,
+) {
+    let Ok(ballot) = api.request("GET", "/lol-honor-v2/v1/ballot", None).await else {
+        return;
+    };
+    let Some(game_id) = ballot.get("gameId").cloned() else {
+        return;
+    };
+    *honored = true;
+
+    let mut allies = ballot_allies(&ballot);
+    let target = if settings.honor_rule == HonorRule::OptOut || allies.is_empty() {
+        None
+    } else {
+        if settings.honor_rule != HonorRule::FixedList {
+            let eog = api.request("GET", "/lol-end-of-game/v1/eog-stats-block", None).await;
+            if let Ok(eog) = eog {
+                apply_eog_stats(&mut allies, &eog);
+            }
+        }
+        let friends = if settings.honor_rule == HonorRule::Premade {
+            friend_puuids(api).await
+        } else {
+            HashSet::new()
+        };
+        choose_target(settings.honor_rule, &allies, &friends, &settings.honor_players)
+    };
+
+    let (body, what) = match target.map(|i| &allies[i]) {
+        Some(ally) => (
+            serde_json::json!({
+                "gameId": game_id,
+                "honorCategory": settings.honor_category.api_name(),
+                "puuid": ally.puuid,
+                "summonerId": ally.summoner_id,
+            }),
+            format!("点赞 {} ({})", ally.name, settings.honor_category.label()),
+        ),
+        None => (
+            serde_json::json!({ "gameId": game_id, "honorCategory": "OPT_OUT" }),
+            "跳过点赞".to_string(),
+        ),
+    };
+    match api.request("POST", "/lol-honor-v2/v1/honor-player", Some(body)).await {
+        Ok(_) => tx.send(GuiMsg::Log(what)).ok(),
+        Err(e) => tx.send(GuiMsg::Log(format!("{}失败: {}", what, e))).ok(),
+    };
+}
+
+fn ballot_allies(ballot: &serde_json::Value) -> Vec<Ally> {
+    let list = ballot
+        .get("eligibleAllies")
+        .or_else(|| ballot.get("eligiblePlayers"))
+        .and_then(|v| v.as_array());
+    list.into_iter()
+        .flatten()
+        .map(|p| Ally {
+            puuid: p["puuid"].as_str().unwrap_or("").to_string(),
+            summoner_id: p["summonerId"].as_i64().unwrap_or(0),
+            name: riot_id(p),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn riot_id(p: &serde_json::Value) -> String {
+    let name = p["gameName"].as_str().or(p["riotIdGameName"].as_str()).unwrap_or("");
+    let tag = p["tagLine"].as_str().or(p["riotIdTagLine"].as_str()).unwrap_or("");
+    match (name, tag) {
+        ("", _) => p["summonerName"].as_str().unwrap_or("").to_string(),
+        (n, "") => n.to_string(),
+        (n, t) => format!("{}#{}", n, t),
+    }
+}
+
+/// 用结算数据补充 KDA 与伤害（按 puuid 匹配，没有 puuid 时按召唤师ID）
+fn apply_eog_stats(allies: &mut [Ally], eog: &serde_json::Value) {
+    let players: Vec<&serde_json::Value> = eog["teams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t["players"].as_array())
+        .flatten()
+        .collect();
+    for ally in allies.iter_mut() {
+        let found = players.iter().find(|p| {
+            if ally.puuid.is_empty() {
+                p["summonerId"].as_i64() == Some(ally.summoner_id)
+            } else {
+                p["puuid"].as_str() == Some(ally.puuid.as_str())
+            }
+        });
+        let Some(stats) = found.map(|p| &p["stats"]) else {
+            continue;
+        };
+        let stat = |key: &str| stats[key].as_i64().unwrap_or(0);
+        let takedowns = stat("CHAMPIONS_KILLED") + stat("ASSISTS");
+        ally.kda = Some(takedowns as f64 / stat("NUM_DEATHS").max(1) as f64);
+        ally.damage = Some(stat("TOTAL_DAMAGE_DEALT_TO_CHAMPIONS"));
+    }
+}
+
+async fn friend_puuids(api: &impl LcuApi) -> HashSet<String> {
+    let friends = api
+        .request("GET", "/lol-chat/v1/friends", None)
+        .await
+        .unwrap_or_default();
+    friends
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f["puuid"].as_str())
+        .map(String::from)
+        .collect()
+}
+
+/// 按规则选出点赞对象的下标，None 表示跳过
+fn choose_target(
+    rule: HonorRule,
+    allies: &[Ally],
+    friends: &HashSet<String>,
+    players: &[String],
+) -> Option<usize> {
+    let best_by = |score: fn(&Ally) -> Option<f64>| {
+        allies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| score(a).map(|s| (i, s)))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(i, _)| i)
+    };
+    let best_kda = || best_by(|a| a.kda);
+    match rule {
+        HonorRule::OptOut => None,
+        HonorRule::Premade => allies
+            .iter()
+            .position(|a| !a.puuid.is_empty() && friends.contains(&a.puuid))
+            .or_else(best_kda),
+        HonorRule::BestKda => best_kda(),
+        HonorRule::MostDamage => best_by(|a| a.damage.map(|d| d as f64)),
+        HonorRule::FixedList => players.iter().find_map(|wanted| {
+            let wanted = wanted.trim().to_lowercase();
+            allies.iter().position(|a| {
+                let name = a.name.to_lowercase();
+                !wanted.is_empty()
+                    && (name == wanted || name.split('#').next() == Some(wanted.as_str()))
+            })
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use crate::models::HonorCategory;
+    use serde_json::json;
+
+    fn ballot() -> serde_json::Value {
+        json!({
+            "gameId": 42,
+            "eligibleAllies": [
+                { "puuid": "p1", "summonerId": 1, "gameName": "Alpha", "tagLine": "CN1" },
+                { "puuid": "p2", "summonerId": 2, "gameName": "Bravo", "tagLine": "CN1" },
+                { "puuid": "p3", "summonerId": 3, "summonerName": "Charlie" }
+            ]
+        })
+    }
+
+    fn eog() -> serde_json::Value {
+        let player = |puuid: &str, k: i64, d: i64, a: i64, dmg: i64| json!({
+            "puuid": puuid,
+            "stats": { "CHAMPIONS_KILLED": k, "NUM_DEATHS": d, "ASSISTS": a, "TOTAL_DAMAGE_DEALT_TO_CHAMPIONS": dmg }
+        });
+        json!({ "teams": [{ "isPlayerTeam": true, "players": [
+            player("p1", 2, 5, 3, 30000),
+            player("p2", 6, 1, 10, 18000),
+            player("p3", 0, 0, 4, 9000)
+        ] }] })
+    }
+
+    fn allies() -> Vec<Ally> {
+        let mut allies = ballot_allies(&ballot());
+        apply_eog_stats(&mut allies, &eog());
+        allies
+    }
+
+    #[test]
+    fn rules_pick_expected_teammate() {
+        let allies = allies();
+        let none = HashSet::new();
+        let pick = |rule, friends: &HashSet<String>, list: &[String]| {
+            choose_target(rule, &allies, friends, list).map(|i| allies[i].name.clone())
+        };
+
+        assert_eq!(pick(HonorRule::BestKda, &none, &[]).as_deref(), Some("Bravo#CN1"));
+        assert_eq!(pick(HonorRule::MostDamage, &none, &[]).as_deref(), Some("Alpha#CN1"));
+        let friends = HashSet::from(["p3".to_string()]);
+        assert_eq!(pick(HonorRule::Premade, &friends, &[]).as_deref(), Some("Charlie"));
+        assert_eq!(pick(HonorRule::Premade, &none, &[]).as_deref(), Some("Bravo#CN1"));
+        let list = ["nobody".to_string(), "alpha".to_string()];
+        assert_eq!(pick(HonorRule::FixedList, &none, &list).as_deref(), Some("Alpha#CN1"));
+        assert_eq!(pick(HonorRule::FixedList, &none, &[]), None);
+        assert_eq!(pick(HonorRule::OptOut, &friends, &list), None);
+    }
+
+    #[tokio::test]
+    async fn honors_chosen_teammate_with_category_once() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-honor-v2/v1/ballot", [ballot()]);
+        api.script("GET", "/lol-end-of-game/v1/eog-stats-block", [eog()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            honor_rule: HonorRule::BestKda,
+            honor_category: HonorCategory::Shotcaller,
+            ..Default::default()
+        };
+        let mut honored = false;
+
+        handle_honor(&api, &s, &tx, &mut honored).await;
+        assert!(honored);
+
+        let posts: Vec<_> = api.requests().into_iter().filter(|r| r.method == "POST").collect();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(
+            posts[0].body,
+            Some(json!({ "gameId": 42, "honorCategory": "SHOTCALLER", "puuid": "p2", "summonerId": 2 }))
+        );
+    }
+
+    #[tokio::test]
+    async fn opt_out_rule_skips_honor() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-honor-v2/v1/ballot", [ballot()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let s = Settings {
+            honor_rule: HonorRule::OptOut,
+            ..Default::default()
+        };
+
+        handle_honor(&api, &s, &tx, &mut false).await;
+
+        let post = api.requests().into_iter().find(|r| r.method == "POST").unwrap();
+        assert_eq!(post.body, Some(json!({ "gameId": 42, "honorCategory": "OPT_OUT" })));
+        assert!(api.requests().iter().all(|r| r.endpoint != "/lol-end-of-game/v1/eog-stats-block"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::{LcuApi, LcuResult};
+use crate::models::{GuiMsg, Hero, ItemBuild};
+use crate::utils::{load_item_builds, lookup_hero_name_by_id};
+
+/// 助手写入的装备方案 uid 前缀，清理时只删除带此前缀的方案
+const MANAGED_UID_PREFIX: &str = "hex-";
+
+/// 我方英雄确定后写入该英雄的装备方案，并移除之前写入的其他方案
+///
+/// 返回 false 表示请求失败（稍后重试）。
+pub async fn apply_item_sets(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    champ_id: i32,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> bool {
+    let builds: Vec<ItemBuild> = load_item_builds()
+        .into_iter()
+        .filter(|b| b.champion_id == champ_id)
+        .collect();
+    if builds.is_empty() {
+        return true;
+    }
+    match replace_managed_sets(api, &builds).await {
+        Ok(true) => {
+            tx.send(GuiMsg::Log(format!(
+                "已写入装备方案: {} ({} 套)",
+                lookup_hero_name_by_id(shared_heroes, champ_id),
+                builds.len()
+            ))).ok();
+            true
+        }
+        Ok(false) => {
+            tx.send(GuiMsg::Log("未获取到召唤师ID，暂不写入装备方案".into())).ok();
+            false
+        }
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("写入装备方案失败: {}", e))).ok();
+            false
+        }
+    }
+}
+
+/// 删除助手写入的所有装备方案，返回 false 表示未获取到召唤师ID
+pub async fn cleanup_item_sets(api: &impl LcuApi) -> LcuResult<bool> {
+    replace_managed_sets(api, &[]).await
+}
+
+/// 用给定的方案替换助手写入的方案，用户自己的方案保持不变
+///
+/// 装备方案按召唤师ID存放，取不到ID时不做任何修改并返回 false。
+async fn replace_managed_sets(api: &impl LcuApi, builds: &[ItemBuild]) -> LcuResult<bool> {
+    let summoner = api.request("GET", "/lol-summoner/v1/current-summoner", None).await?;
+    let Some(summoner_id) = summoner.get("summonerId").and_then(|v| v.as_i64()).filter(|&id| id > 0)
+    else {
+        return Ok(false);
+    };
+    let endpoint = format!("/lol-item-sets/v1/item-sets/{}/sets", summoner_id);
+
+    let mut doc = api.request("GET", &endpoint, None).await?;
+    let mut sets: Vec<serde_json::Value> = doc
+        .get("itemSets")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    sets.retain(|s| {
+        !s.get("uid")
+            .and_then(|v| v.as_str())
+            .is_some_and(|uid| uid.starts_with(MANAGED_UID_PREFIX))
+    });
+    sets.extend(builds.iter().enumerate().map(|(i, b)| item_set_json(b, i)));
+
+    doc["itemSets"] = serde_json::Value::Array(sets);
+    api.request("PUT", &endpoint, Some(doc)).await?;
+    Ok(true)
+}
+
+/// 转换为客户端的装备方案格式
+fn item_set_json(build: &ItemBuild, index: usize) -> serde_json::Value {
+    serde_json::json!({
+        "uid": format!("{}{}-{}", MANAGED_UID_PREFIX, build.champion_id, index),
+        "title": build.title,
+        "associatedChampions": [build.champion_id],
+        "associatedMaps": build.maps,
+        "blocks": build.blocks.iter().map(|block| serde_json::json!({
+            "type": block.name,
+            "items": block.items.iter().map(|id| serde_json::json!({
+                "id": id.to_string(),
+                "count": 1,
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "map": "any",
+        "mode": "any",
+        "preferredItemSlots": [],
+        "sortrank": index,
+        "startedFrom": "blank",
+        "type": "custom",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use crate::models::ItemBlock;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn keeps_user_sets_and_replaces_managed_ones() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-summoner/v1/current-summoner", [json!({ "summonerId": 42 })]);
+        api.script(
+            "GET",
+            "/lol-item-sets/v1/item-sets/42/sets",
+            [json!({
+                "accountId": 42,
+                "itemSets": [
+                    { "uid": "user-set", "title": "我的出装" },
+                    { "uid": "hex-157-0", "title": "旧方案" }
+                ],
+                "timestamp": 1
+            })],
+        );
+        let build = ItemBuild {
+            champion_id: 103,
+            title: "法穿".into(),
+            maps: vec![11],
+            blocks: vec![ItemBlock {
+                name: "出门".into(),
+                items: vec![1056, 2003],
+            }],
+        };
+
+        assert!(replace_managed_sets(&api, &[build]).await.unwrap());
+
+        let put = api.requests().into_iter().find(|r| r.method == "PUT").unwrap();
+        assert_eq!(put.endpoint, "/lol-item-sets/v1/item-sets/42/sets");
+        let body = put.body.unwrap();
+        let uids: Vec<&str> = body["itemSets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["uid"].as_str().unwrap())
+            .collect();
+        assert_eq!(uids, ["user-set", "hex-103-0"]);
+        assert_eq!(body["itemSets"][1]["blocks"][0]["items"][0], json!({ "id": "1056", "count": 1 }));
+        assert_eq!(body["timestamp"], json!(1));
+    }
+
+    #[tokio::test]
+    async fn missing_summoner_id_leaves_sets_alone() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-summoner/v1/current-summoner", [json!({ "displayName": "?" })]);
+
+        assert!(!cleanup_item_sets(&api).await.unwrap());
+        assert_eq!(api.requests().len(), 1);
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::lcu::LcuApi;
+use crate::models::{ChampSelectState, GuiMsg, Hero, Settings};
+use crate::utils::{lookup_hero_name_by_id, spell_name};
+
+use super::item_sets::apply_item_sets;
+use super::runes::apply_runes;
+use super::skins::apply_skin;
+
+const FLASH: i32 = 4;
+const SMITE: i32 = 11;
+/// 同一英雄最多应用召唤师技能/符文/装备方案的次数
+const LOADOUT_MAX_ATTEMPTS: u32 = 3;
+/// 应用失败后的重试间隔
+const LOADOUT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// 同一英雄最多读取皮肤列表的次数
+const SKIN_MAX_ATTEMPTS: u32 = 10;
+/// 皮肤列表未就绪时的重试间隔
+const SKIN_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 我方英雄确定后应用召唤师技能、符文、装备方案和皮肤
+///
+/// 峡谷以自己的选人 action 完成为准，大乱斗使用当前分配到的英雄。
+/// 英雄变化（如板凳席交换）后会重新应用；失败的步骤间隔一段时间后重试，有次数上限。
+/// 斗魂竞技场没有可选的召唤师技能和符文，只处理装备方案和皮肤。
+pub async fn handle_loadout(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    cs: &mut ChampSelectState,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) {
+    let Some(me) = session
+        .get("myTeam")
+        .and_then(|v| v.as_array())
+        .and_then(|tm| {
+            tm.iter()
+                .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(local_cell_id))
+        })
+    else {
+        return;
+    };
+
+    let champ_id = locked_champion(session, local_cell_id, me);
+    if champ_id <= 0 {
+        return;
+    }
+    // 皮肤列表可能稍晚才切换到新英雄，未就绪时间隔一段时间再试，超过次数后放弃；
+    // 既不自动选择也没有展开收藏选择时不需要皮肤列表
+    if champ_id != cs.skin_champion
+        && (settings.auto_skin || cs.skin_picker)
+        && try_attempt(&mut cs.skin_attempts, champ_id, SKIN_MAX_ATTEMPTS, SKIN_RETRY_INTERVAL)
+        && apply_skin(api, settings, tx, me, champ_id).await
+    {
+        cs.skin_champion = champ_id;
+        cs.skin_attempts.remove(&champ_id);
+    }
+    let spells = settings.auto_spells && !cs.arena && cs.spells_champion != champ_id;
+    let runes = settings.auto_runes && !cs.arena && cs.runes_champion != champ_id;
+    let item_sets = settings.auto_item_sets && cs.item_sets_champion != champ_id;
+    if !(spells || runes || item_sets) {
+        return;
+    }
+    let attempts = &mut cs.loadout_attempts;
+    if !try_attempt(attempts, champ_id, LOADOUT_MAX_ATTEMPTS, LOADOUT_RETRY_INTERVAL) {
+        return;
+    }
+
+    let position = if session.get("benchEnabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+        "aram".to_string()
+    } else if cs.arena {
+        "arena".to_string()
+    } else {
+        me.get("assignedPosition")
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_lowercase()
+    };
+
+    if spells && apply_spells(api, settings, tx, me, champ_id, &position, shared_heroes).await {
+        cs.spells_champion = champ_id;
+    }
+    if runes && apply_runes(api, tx, champ_id, &position, shared_heroes).await {
+        cs.runes_champion = champ_id;
+    }
+    if item_sets && apply_item_sets(api, tx, champ_id, shared_heroes).await {
+        cs.item_sets_champion = champ_id;
+    }
+    let done = |c: i32, pending: bool| !pending || c == champ_id;
+    if done(cs.spells_champion, spells)
+        && done(cs.runes_champion, runes)
+        && done(cs.item_sets_champion, item_sets)
+    {
+        cs.loadout_attempts.remove(&champ_id);
+    }
+}
+
+/// 记录对该英雄的一次尝试，返回 false 表示已达到次数上限或未到重试时间
+fn try_attempt(
+    attempts: &mut HashMap<i32, (u32, Instant)>,
+    champ_id: i32,
+    max: u32,
+    interval: Duration,
+) -> bool {
+    let now = Instant::now();
+    match attempts.get(&champ_id).copied() {
+        Some((n, _)) if n >= max => false,
+        Some((_, last)) if now.duration_since(last) < interval => false,
+        prev => {
+            attempts.insert(champ_id, (prev.map_or(0, |(n, _)| n) + 1, now));
+            true
+        }
+    }
+}
+
+/// 自己已确定的英雄：有选人 action 时以完成的 action 为准，否则取队伍信息
+fn locked_champion(session: &serde_json::Value, local_cell_id: i64, me: &serde_json::Value) -> i32 {
+    let actions = session.get("actions").and_then(|v| v.as_array());
+    let own_picks: Vec<&serde_json::Value> = actions
+        .into_iter()
+        .flatten()
+        .filter_map(|g| g.as_array())
+        .flatten()
+        .filter(|a| {
+            a.get("actorCellId").and_then(|v| v.as_i64()) == Some(local_cell_id)
+                && a.get("type").and_then(|v| v.as_str()) == Some("pick")
+        })
+        .collect();
+
+    let champ = if own_picks.is_empty() {
+        me.get("championId")
+    } else {
+        own_picks
+            .iter()
+            .find(|a| a.get("completed").and_then(|v| v.as_bool()).unwrap_or(false))
+            .and_then(|a| a.get("championId"))
+    };
+    champ.and_then(|v| v.as_i64()).unwrap_or(0) as i32
+}
+
+/// 计算要使用的召唤师技能 [D, F]，没有配置时返回 None
+///
+/// 英雄配置优先于位置配置；打野强制携带惩戒（替换闪现以外的技能）。
+fn choose_spells(settings: &Settings, champ_id: i32, position: &str) -> Option<[i32; 2]> {
+    let mut pair = settings
+        .spells_by_champion
+        .get(&champ_id)
+        .or_else(|| settings.spells_by_role.get(position))
+        .copied();
+
+    if position == "jungle" {
+        let [d, f] = pair.unwrap_or([FLASH, SMITE]);
+        pair = Some(match (d, f) {
+            _ if d == SMITE || f == SMITE => [d, f],
+            (FLASH, _) => [FLASH, SMITE],
+            _ => [SMITE, f],
+        });
+    }
+
+    pair.map(|[d, f]| {
+        let other = if d == FLASH { f } else { d };
+        match (d == FLASH || f == FLASH, settings.flash_on_f) {
+            (true, true) => [other, FLASH],
+            (true, false) => [FLASH, other],
+            (false, _) => [d, f],
+        }
+    })
+}
+
+/// 设置召唤师技能，返回 false 表示请求失败（稍后重试）
+async fn apply_spells(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    me: &serde_json::Value,
+    champ_id: i32,
+    position: &str,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> bool {
+    let Some([d, f]) = choose_spells(settings, champ_id, position) else {
+        return true;
+    };
+    let current = [
+        me.get("spell1Id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+        me.get("spell2Id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+    ];
+    if current == [d, f] {
+        return true;
+    }
+
+    let body = serde_json::json!({ "spell1Id": d, "spell2Id": f });
+    match api
+        .request("PATCH", "/lol-champ-select/v1/session/my-selection", Some(body))
+        .await
+    {
+        Ok(_) => {
+            tx.send(GuiMsg::Log(format!(
+                "召唤师技能 ({}): {} + {}",
+                lookup_hero_name_by_id(shared_heroes, champ_id),
+                spell_name(d),
+                spell_name(f)
+            ))).ok();
+            true
+        }
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("设置召唤师技能失败: {}", e))).ok();
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn patches(api: &FakeLcu) -> Vec<crate::fake_lcu::RecordedRequest> {
+        api.requests().into_iter().filter(|r| r.method == "PATCH").collect()
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            auto_spells: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn jungle_always_takes_smite_and_flash_follows_ordering() {
+        let mut s = settings();
+        s.spells_by_role.insert("jungle".into(), [14, 4]);
+        assert_eq!(choose_spells(&s, 64, "jungle"), Some([4, 11]));
+
+        s.flash_on_f = true;
+        s.spells_by_champion.insert(157, [12, 4]);
+        assert_eq!(choose_spells(&s, 157, "middle"), Some([12, 4]));
+        assert_eq!(choose_spells(&s, 103, "middle"), Some([14, 4]));
+        assert_eq!(choose_spells(&s, 103, ""), None);
+    }
+
+    #[tokio::test]
+    async fn spells_wait_for_locked_pick() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+        let mut session = json!({
+            "localPlayerCellId": 2,
+            "myTeam": [{ "cellId": 2, "assignedPosition": "middle", "championId": 103, "spell1Id": 4, "spell2Id": 7 }],
+            "actions": [[{ "id": 9, "actorCellId": 2, "type": "pick", "completed": false, "championId": 103 }]]
+        });
+
+        handle_loadout(&api, &settings(), &tx, &session, 2, &mut cs, &heroes).await;
+        assert!(patches(&api).is_empty());
+
+        session["actions"][0][0]["completed"] = json!(true);
+        handle_loadout(&api, &settings(), &tx, &session, 2, &mut cs, &heroes).await;
+        handle_loadout(&api, &settings(), &tx, &session, 2, &mut cs, &heroes).await;
+
+        let reqs = patches(&api);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].endpoint, "/lol-champ-select/v1/session/my-selection");
+        assert_eq!(reqs[0].body, Some(json!({ "spell1Id": 4, "spell2Id": 14 })));
+    }
+
+    #[tokio::test]
+    async fn aram_reapplies_after_bench_swap() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+        let mut s = settings();
+        s.spells_by_champion.insert(222, [4, 7]);
+        let mut session = json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "myTeam": [{ "cellId": 0, "championId": 1, "spell1Id": 4, "spell2Id": 32 }]
+        });
+
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+        assert!(patches(&api).is_empty());
+
+        session["myTeam"][0]["championId"] = json!(222);
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+
+        let reqs = patches(&api);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].body, Some(json!({ "spell1Id": 4, "spell2Id": 7 })));
+    }
+
+    #[tokio::test]
+    async fn skin_carousel_is_fetched_only_when_needed_and_capped() {
+        let api = FakeLcu::new();
+        let carousel = "/lol-champ-select/v1/skin-carousel-skins";
+        // 皮肤列表一直是上一个英雄的
+        api.script("GET", carousel, [json!([{ "id": 1000, "championId": 1 }])]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+        let session = json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "myTeam": [{ "cellId": 0, "championId": 222 }]
+        });
+        let fetches = |api: &FakeLcu| api.requests().iter().filter(|r| r.endpoint == carousel).count();
+
+        handle_loadout(&api, &Settings::default(), &tx, &session, 0, &mut cs, &heroes).await;
+        assert_eq!(fetches(&api), 0);
+
+        cs.skin_picker = true;
+        handle_loadout(&api, &Settings::default(), &tx, &session, 0, &mut cs, &heroes).await;
+        handle_loadout(&api, &Settings::default(), &tx, &session, 0, &mut cs, &heroes).await;
+        assert_eq!(fetches(&api), 1);
+
+        for _ in 0..SKIN_MAX_ATTEMPTS + 2 {
+            let (n, last) = cs.skin_attempts[&222];
+            cs.skin_attempts.insert(222, (n, last - SKIN_RETRY_INTERVAL));
+            handle_loadout(&api, &Settings::default(), &tx, &session, 0, &mut cs, &heroes).await;
+        }
+        assert_eq!(fetches(&api), SKIN_MAX_ATTEMPTS as usize);
+        assert_eq!(cs.skin_champion, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_spells_patch_is_retried() {
+        let api = FakeLcu::new();
+        let selection = "/lol-champ-select/v1/session/my-selection";
+        api.script_error("PATCH", selection, 500, "RPC_ERROR", "Not ready");
+        api.script("PATCH", selection, [json!(null)]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+        let mut s = settings();
+        s.spells_by_champion.insert(222, [4, 7]);
+        let session = json!({
+            "localPlayerCellId": 0,
+            "benchEnabled": true,
+            "myTeam": [{ "cellId": 0, "championId": 222, "spell1Id": 4, "spell2Id": 32 }]
+        });
+
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+        assert_eq!(cs.spells_champion, 0);
+        // 未到重试时间
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+        assert_eq!(patches(&api).len(), 1);
+
+        let (n, last) = cs.loadout_attempts[&222];
+        cs.loadout_attempts.insert(222, (n, last - LOADOUT_RETRY_INTERVAL));
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+        handle_loadout(&api, &s, &tx, &session, 0, &mut cs, &heroes).await;
+
+        assert_eq!(patches(&api).len(), 2);
+        assert_eq!(cs.spells_champion, 222);
+        assert!(cs.loadout_attempts.is_empty());
+    }
+}
@@ -1,19 +1,54 @@
 mod champ_select;
+mod chat;
+mod draft;
+mod events;
+mod export;
 mod flow;
+mod history;
+mod honor;
+mod item_sets;
+mod loadout;
+mod replay;
+mod runes;
+mod skins;
+mod swaps;
+mod teammates;
+mod trades;
 
 use base64::prelude::*;
 use reqwest::Client;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use sysinfo::{ProcessesToUpdate, System};
 
-use crate::lcu::{find_lcu_process, lcu_request, spoof_rank, update_data};
-use crate::models::{ActionState, BackendMsg, GuiMsg, Hero, LcuConnection};
-use crate::utils::{load_settings, save_settings_to_disk};
+use crate::capture::Recorder;
+use crate::lcu::{
+    find_lcu_lockfile, find_lcu_process, lcu_override, spoof_rank, update_data, HttpLcu, LcuApi,
+    LcuError,
+};
+use crate::models::{BackendMsg, ChampSelectState, GuiMsg, Hero, LcuConnection, Settings};
+use crate::utils::{
+    history_path, load_game_history, load_rune_presets, load_settings, save_rune_presets,
+    save_settings_to_disk, EXPORT_DIR,
+};
 
 use champ_select::handle_champ_select;
+use events::{spawn_event_listener, LcuEvent};
+use export::{export_history, export_last_game};
 use flow::{handle_end_of_game, handle_lobby, handle_ready_check};
+use history::record_game;
+use item_sets::cleanup_item_sets;
+use runes::{import_current_page, upsert_preset};
+use teammates::handle_teammates;
+
+pub use replay::run_replay;
+
+/// 事件通道正常时，仍以此间隔轮询一次游戏阶段作为兜底
+const PHASE_POLL_FALLBACK: Duration = Duration::from_secs(5);
+/// 事件通道断开后重新订阅的间隔
+const EVENT_RETRY_INTERVAL: Duration = Duration::from_secs(10);
 
 pub async fn run_backend(
     tx: crossbeam_channel::Sender<GuiMsg>,
@@ -26,17 +61,10 @@ pub async fn run_backend(
         .timeout(Duration::from_secs(3))
         .build()
         .unwrap();
-    let mut connection: Option<LcuConnection> = None;
-
-    // Logic State
-    let mut last_phase = String::new();
-    let mut honored = false;
-    let mut played_again = false;
-    let mut queue_timer: Option<Instant> = None;
-    let mut last_bench_ids: Vec<i32> = Vec::new();
-
-    // Action Tracking (ActionID -> State)
-    let mut handled_actions: HashMap<i64, ActionState> = HashMap::new();
+    let mut connection: Option<HttpLcu> = None;
+    let mut flow = FlowState::default();
+    // 界面的皮肤收藏选择是否展开
+    let mut skin_picker = false;
 
     tx.send(GuiMsg::LoadedData("检查数据...".into())).unwrap();
     update_data(&client, &shared_heroes).await;
@@ -47,28 +75,111 @@ pub async fn run_backend(
         while let Ok(msg) = rx.try_recv() {
             match msg {
                 BackendMsg::SaveSettings(s) => {
+                    let capture_changed = s.capture != settings.capture;
                     settings = s;
                     save_settings_to_disk(&settings);
+                    tx.send(GuiMsg::ActiveProfile {
+                        queue_id: flow.queue_id,
+                        profile: settings.profile_for(flow.queue_id).map(|p| p.name.clone()),
+                    })
+                    .unwrap();
+                    if capture_changed {
+                        if let Some(api) = connection.as_mut() {
+                            api.recorder = start_capture(&settings, &tx);
+                        }
+                    }
                 }
                 BackendMsg::SwapChamp(id) => {
-                    if let Some(conn) = &connection {
-                        let _ = lcu_request(
-                            &client,
-                            conn,
-                            "POST",
-                            &format!("/lol-champ-select/v1/session/bench/swap/{}", id),
-                            None,
-                        )
-                        .await;
+                    if let Some(api) = &connection {
+                        if let Err(e) = api
+                            .request(
+                                "POST",
+                                &format!("/lol-champ-select/v1/session/bench/swap/{}", id),
+                                None,
+                            )
+                            .await
+                        {
+                            tx.send(GuiMsg::Log(format!("交换失败: {}", e))).unwrap();
+                        }
                     }
                 }
                 BackendMsg::UpdateRank => {
-                    if let Some(conn) = &connection {
-                        spoof_rank(&client, conn, &settings).await;
+                    if let Some(api) = &connection {
+                        if let Err(e) = spoof_rank(api, &settings).await {
+                            tx.send(GuiMsg::Log(format!("伪装段位失败: {}", e))).unwrap();
+                        }
+                    }
+                }
+                BackendMsg::SaveRunePresets(presets) => save_rune_presets(&presets),
+                BackendMsg::ImportRunePage {
+                    champion_id,
+                    position,
+                } => {
+                    let Some(api) = &connection else {
+                        tx.send(GuiMsg::Log("未连接客户端，无法导入符文页".into())).unwrap();
+                        continue;
+                    };
+                    match import_current_page(api, champion_id, position).await {
+                        Ok(preset) => {
+                            tx.send(GuiMsg::Log(format!("已导入符文页: {}", preset.name))).unwrap();
+                            let mut presets = load_rune_presets();
+                            upsert_preset(&mut presets, preset);
+                            save_rune_presets(&presets);
+                            tx.send(GuiMsg::RunePresets(presets)).unwrap();
+                        }
+                        Err(e) => {
+                            tx.send(GuiMsg::Log(format!("导入符文页失败: {}", e))).unwrap();
+                        }
+                    }
+                }
+                BackendMsg::CleanupItemSets => {
+                    if let Some(api) = &connection {
+                        match cleanup_item_sets(api).await {
+                            Ok(true) => tx.send(GuiMsg::Log("已清除助手写入的装备方案".into())).unwrap(),
+                            Ok(false) => tx
+                                .send(GuiMsg::Log("未获取到召唤师ID，无法清除装备方案".into()))
+                                .unwrap(),
+                            Err(e) => {
+                                tx.send(GuiMsg::Log(format!("清除装备方案失败: {}", e))).unwrap()
+                            }
+                        }
+                    }
+                }
+                BackendMsg::ExportLastGame => {
+                    let Some(api) = &connection else {
+                        tx.send(GuiMsg::Log("未连接客户端，无法导出结算数据".into())).unwrap();
+                        continue;
+                    };
+                    match export_last_game(api, Path::new(EXPORT_DIR), &shared_heroes).await {
+                        Ok((csv, json)) => tx
+                            .send(GuiMsg::Log(format!(
+                                "已导出: {} / {}",
+                                csv.display(),
+                                json.display()
+                            )))
+                            .unwrap(),
+                        Err(e) => tx.send(GuiMsg::Log(format!("导出失败: {:#}", e))).unwrap(),
+                    }
+                }
+                BackendMsg::ExportHistory { puuid, from, to } => {
+                    let records = load_game_history(&history_path(&puuid));
+                    let dir = Path::new(EXPORT_DIR);
+                    match export_history(&records, from, to, dir, &shared_heroes) {
+                        Ok((csv, json, count)) => tx
+                            .send(GuiMsg::Log(format!(
+                                "已导出 {} 局: {} / {}",
+                                count,
+                                csv.display(),
+                                json.display()
+                            )))
+                            .unwrap(),
+                        Err(e) => tx.send(GuiMsg::Log(format!("导出失败: {:#}", e))).unwrap(),
                     }
                 }
+                BackendMsg::SkinPicker(open) => skin_picker = open,
                 BackendMsg::ForceReconnect => {
                     connection = None;
+                    flow.close_events();
                     tx.send(GuiMsg::Status(false)).unwrap();
                     tx.send(GuiMsg::Log("正在重连...".into())).unwrap();
                     tokio::time::sleep(Duration::from_millis(500)).await;
@@ -78,29 +189,39 @@ pub async fn run_backend(
 
         // 2. Connection Handling
         if connection.is_none() {
-            // Re-initialize System to ensure we capture newly started processes correctly
-            let mut sys = System::new_all();
-            sys.refresh_processes(ProcessesToUpdate::All, true);
-            match find_lcu_process(&sys) {
-                Some((port, token)) => {
+            let found = lcu_override().or_else(|| {
+                // Re-initialize System to ensure we capture newly started processes correctly
+                let mut sys = System::new_all();
+                sys.refresh_processes(ProcessesToUpdate::All, true);
+                find_lcu_lockfile(&sys, &settings.client_path)
+                    .or_else(|| find_lcu_process(&sys))
+                    .map(|(port, token)| (format!("https://127.0.0.1:{}", port), token))
+            });
+            match found {
+                Some((url, token)) => {
                     let auth = format!(
                         "Basic {}",
                         BASE64_STANDARD.encode(format!("riot:{}", token))
                     );
-                    connection = Some(LcuConnection {
-                        url: format!("https://127.0.0.1:{}", port),
-                        auth_header: auth,
+                    connection = Some(HttpLcu {
+                        client: client.clone(),
+                        conn: LcuConnection {
+                            url,
+                            auth_header: auth,
+                        },
+                        recorder: start_capture(&settings, &tx),
                     });
                     tx.send(GuiMsg::Log("已连接客户端".into())).unwrap();
                     tx.send(GuiMsg::Status(true)).unwrap();
 
                     // Reset State
-                    last_phase = "None".into();
-                    handled_actions.clear();
+                    flow.reset(spawn_event_listener(&connection.as_ref().unwrap().conn));
 
                     if settings.spoof_rank {
                         tokio::time::sleep(Duration::from_millis(500)).await;
-                        spoof_rank(&client, connection.as_ref().unwrap(), &settings).await;
+                        if let Err(e) = spoof_rank(connection.as_ref().unwrap(), &settings).await {
+                            tx.send(GuiMsg::Log(format!("伪装段位失败: {}", e))).unwrap();
+                        }
                     }
                 }
                 None => {
@@ -111,84 +232,395 @@ pub async fn run_backend(
             }
         }
 
-        let conn = connection.as_ref().unwrap().clone();
-
-        // 3. Gameflow Phase
-        let phase_val = match lcu_request(
-            &client,
-            &conn,
-            "GET",
-            "/lol-gameflow/v1/gameflow-phase",
-            None,
-        )
-        .await
-        {
-            Ok(v) => v,
-            Err(_) => {
-                tx.send(GuiMsg::Log("连接断开".into())).unwrap();
-                tx.send(GuiMsg::Status(false)).unwrap();
-                connection = None;
-                continue;
+        let api = connection.as_ref().unwrap().clone();
+
+        // 事件通道断开后定期尝试重新订阅
+        if flow.events.is_none() && flow.event_retry_at.is_some_and(|t| Instant::now() >= t) {
+            flow.events = Some(spawn_event_listener(&api.conn));
+            flow.event_retry_at = None;
+        }
+
+        // 3. Gameflow Phase & 4. Phase Specific Logic
+        let Some(loop_delay) = flow
+            .update(&api, &settings, skin_picker, &tx, &shared_heroes)
+            .await
+        else {
+            connection = None;
+            continue;
+        };
+
+        // 5. Wait for next tick or pushed event
+        flow.wait(loop_delay, api.recorder.as_deref(), &tx).await;
+    }
+}
+
+/// 游戏流程的运行状态，连接客户端后重置事件与阶段相关的部分
+#[derive(Default)]
+struct FlowState {
+    events: Option<tokio::sync::mpsc::UnboundedReceiver<LcuEvent>>,
+    /// 事件通道断开后下一次重新订阅的时间
+    event_retry_at: Option<Instant>,
+    pushed_phase: Option<String>,
+    pushed_session: Option<serde_json::Value>,
+    last_phase_poll: Option<Instant>,
+    last_phase: String,
+    honored: bool,
+    played_again: bool,
+    /// 本局是否已写入本地战绩
+    recorded: bool,
+    queue_timer: Option<Instant>,
+    /// 本次就绪检查最近一次接受失败的提示
+    ready_check_error: Option<String>,
+    /// 当前队列，用于选择队列方案
+    queue_id: Option<i64>,
+    /// Champ Select State (actions, bench, available champions)
+    champ_select: ChampSelectState,
+}
+
+impl FlowState {
+    /// 连接客户端后重置阶段与事件状态
+    fn reset(&mut self, events: tokio::sync::mpsc::UnboundedReceiver<LcuEvent>) {
+        self.last_phase = "None".into();
+        self.queue_id = None;
+        self.champ_select = ChampSelectState::default();
+        self.pushed_phase = None;
+        self.pushed_session = None;
+        self.last_phase_poll = None;
+        self.events = Some(events);
+        self.event_retry_at = None;
+    }
+
+    /// 关闭事件通道，同时丢弃已推送的阶段与会话，之后改为轮询获取最新状态
+    fn close_events(&mut self) {
+        self.events = None;
+        self.pushed_phase = None;
+        self.pushed_session = None;
+    }
+
+    /// 获取当前阶段并执行对应的自动操作
+    ///
+    /// 返回下一次处理前的等待时间，连接断开时返回 None。
+    async fn update<A: LcuApi + Clone + Send + 'static>(
+        &mut self,
+        api: &A,
+        settings: &Settings,
+        skin_picker: bool,
+        tx: &crossbeam_channel::Sender<GuiMsg>,
+        shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+    ) -> Option<Duration> {
+        // 有事件推送时优先使用推送的阶段，仅按兜底间隔轮询
+        let should_poll = self.events.is_none()
+            || self.last_phase_poll.is_none_or(|t| t.elapsed() >= PHASE_POLL_FALLBACK);
+        let polled_phase = if should_poll {
+            self.last_phase_poll = Some(Instant::now());
+            match api.request("GET", "/lol-gameflow/v1/gameflow-phase", None).await {
+                Ok(v) => Some(v.as_str().unwrap_or("None").to_string()),
+                // 客户端有响应但返回错误，保持上一个阶段
+                Err(e @ LcuError::Http { .. }) => {
+                    tx.send(GuiMsg::Log(format!("获取游戏阶段失败: {}", e))).unwrap();
+                    None
+                }
+                Err(LcuError::Transport(_)) => {
+                    tx.send(GuiMsg::Log("连接断开".into())).unwrap();
+                    tx.send(GuiMsg::Status(false)).unwrap();
+                    self.close_events();
+                    return None;
+                }
             }
+        } else {
+            None
         };
 
-        let phase = phase_val.as_str().unwrap_or("None").to_string();
-        if phase != last_phase {
-            last_phase = phase.clone();
+        let phase = polled_phase
+            .or(self.pushed_phase.take())
+            .unwrap_or_else(|| self.last_phase.clone());
+        if phase != self.last_phase {
+            self.last_phase = phase.clone();
             tx.send(GuiMsg::Log(format!("状态: {}", phase))).unwrap();
 
+            // 阶段变化时刷新当前队列
+            let new_queue = if phase == "None" {
+                None
+            } else {
+                current_queue_id(api).await
+            };
+            if new_queue != self.queue_id {
+                self.queue_id = new_queue;
+                let profile = settings.profile_for(self.queue_id).map(|p| p.name.clone());
+                if let Some(name) = &profile {
+                    tx.send(GuiMsg::Log(format!("使用队列方案: {}", name))).unwrap();
+                }
+                tx.send(GuiMsg::ActiveProfile { queue_id: self.queue_id, profile }).unwrap();
+            }
+
+            if phase == "ReadyCheck" {
+                self.ready_check_error = None;
+            }
             // Phase Change Reset - 进入新的 ChampSelect 时清除旧状态
             if phase == "ChampSelect" {
-                handled_actions.clear();
+                self.champ_select = ChampSelectState::default();
+                if settings.teammate_lookup {
+                    tokio::spawn(handle_teammates(
+                        api.clone(),
+                        tx.clone(),
+                        settings.teammate_games,
+                    ));
+                }
+            } else {
+                self.pushed_session = None;
+                tx.send(GuiMsg::Draft(None)).unwrap();
+                tx.send(GuiMsg::Teammates(vec![])).unwrap();
             }
             if phase == "Lobby" {
-                honored = false;
-                played_again = false;
-                queue_timer = None;
+                self.honored = false;
+                self.played_again = false;
+                self.recorded = false;
+                self.queue_timer = None;
                 if settings.spoof_rank {
-                    spoof_rank(&client, &conn, &settings).await;
+                    if let Err(e) = spoof_rank(api, settings).await {
+                        tx.send(GuiMsg::Log(format!("伪装段位失败: {}", e))).unwrap();
+                    }
                 }
             }
         }
 
-        // 4. Phase Specific Logic
         let mut loop_delay = Duration::from_secs(2);
+        // 叠加当前队列方案后的设置
+        let active = settings.for_queue(self.queue_id);
 
         match phase.as_str() {
             "ReadyCheck" => {
                 loop_delay = Duration::from_millis(500);
-                handle_ready_check(&client, &conn, &settings).await;
+                handle_ready_check(api, &active, tx, &mut self.ready_check_error).await;
             }
             "ChampSelect" => {
                 loop_delay = Duration::from_millis(200); // Fast tick for locking
+                self.champ_select.skin_picker = skin_picker;
                 handle_champ_select(
-                    &client,
-                    &conn,
-                    &settings,
-                    &tx,
-                    &mut last_bench_ids,
-                    &mut handled_actions,
-                    &shared_heroes,
+                    api,
+                    &active,
+                    self.queue_id,
+                    tx,
+                    self.pushed_session.as_ref(),
+                    &mut self.champ_select,
+                    shared_heroes,
                 )
                 .await;
             }
             "PreEndOfGame" | "EndOfGame" | "WaitingForStats" => {
+                // 先记录战绩，自动返回房间后就离开结算阶段了
+                if phase == "EndOfGame" {
+                    record_game(api, tx, self.queue_id, &mut self.recorded).await;
+                }
                 handle_end_of_game(
-                    &client,
-                    &conn,
-                    &settings,
-                    &tx,
-                    &mut honored,
-                    &mut played_again,
+                    api,
+                    &active,
+                    tx,
+                    &mut self.honored,
+                    &mut self.played_again,
                 )
                 .await;
             }
             "Lobby" => {
-                handle_lobby(&client, &conn, &settings, &tx, &mut queue_timer).await;
+                handle_lobby(api, &active, tx, &mut self.queue_timer).await;
             }
             _ => {}
         }
+        Some(loop_delay)
+    }
 
-        tokio::time::sleep(loop_delay).await;
+    /// 等待下一次处理，期间收到推送的事件时立即返回
+    async fn wait(
+        &mut self,
+        loop_delay: Duration,
+        recorder: Option<&Recorder>,
+        tx: &crossbeam_channel::Sender<GuiMsg>,
+    ) {
+        let pushed = match self.events.as_mut() {
+            Some(rx) => tokio::select! {
+                ev = rx.recv() => Some(ev),
+                _ = tokio::time::sleep(loop_delay) => None,
+            },
+            None => {
+                tokio::time::sleep(loop_delay).await;
+                None
+            }
+        };
+        match pushed {
+            Some(Some(ev)) => {
+                // 合并同一时刻到达的多个事件，只保留最新状态
+                let mut batch = vec![ev];
+                if let Some(rx) = self.events.as_mut() {
+                    while let Ok(ev) = rx.try_recv() {
+                        batch.push(ev);
+                    }
+                }
+                for ev in batch {
+                    if let Some(rec) = recorder {
+                        match &ev {
+                            LcuEvent::Phase(p) => rec.record_event(
+                                "/lol-gameflow/v1/gameflow-phase",
+                                Some(&serde_json::json!(p)),
+                            ),
+                            LcuEvent::ChampSelect(s) => {
+                                rec.record_event("/lol-champ-select/v1/session", s.as_ref())
+                            }
+                            LcuEvent::Lobby | LcuEvent::Matchmaking => {}
+                        }
+                    }
+                    match ev {
+                        LcuEvent::Phase(p) => self.pushed_phase = Some(p),
+                        LcuEvent::ChampSelect(s) => self.pushed_session = s,
+                        // 房间/匹配变化只需立即唤醒一次循环
+                        LcuEvent::Lobby | LcuEvent::Matchmaking => {}
+                    }
+                }
+            }
+            Some(None) => {
+                self.close_events();
+                self.event_retry_at = Some(Instant::now() + EVENT_RETRY_INTERVAL);
+                tx.send(GuiMsg::Log("事件推送断开，改为轮询".into())).unwrap();
+            }
+            None => {}
+        }
+    }
+}
+
+/// 根据设置开启录制，返回录制器
+fn start_capture(
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+) -> Option<Arc<Recorder>> {
+    if !settings.capture {
+        return None;
+    }
+    match Recorder::create(settings) {
+        Ok(rec) => {
+            tx.send(GuiMsg::Log(format!("开始录制: {}", rec.path.display())))
+                .unwrap();
+            Some(Arc::new(rec))
+        }
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("无法创建录制文件: {}", e))).unwrap();
+            None
+        }
+    }
+}
+
+/// 读取当前对局/房间的队列ID
+async fn current_queue_id(api: &impl LcuApi) -> Option<i64> {
+    let session = api.request("GET", "/lol-gameflow/v1/session", None).await.ok()?;
+    session
+        .get("gameData")
+        .and_then(|g| g.get("queue"))
+        .and_then(|q| q.get("id"))
+        .and_then(|v| v.as_i64())
+        .filter(|&id| id > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    const PHASE: &str = "/lol-gameflow/v1/gameflow-phase";
+
+    fn phase_polls(api: &FakeLcu) -> usize {
+        api.requests().iter().filter(|r| r.endpoint == PHASE).count()
+    }
+
+    #[tokio::test]
+    async fn closed_event_stream_falls_back_to_polling_phase() {
+        let api = FakeLcu::new();
+        api.script("GET", PHASE, [json!("ChampSelect")]);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+        let settings = Settings {
+            teammate_lookup: false,
+            ..Default::default()
+        };
+
+        let (event_tx, events) = tokio::sync::mpsc::unbounded_channel();
+        let mut flow = FlowState::default();
+        flow.reset(events);
+        event_tx.send(LcuEvent::Phase("Lobby".into())).unwrap();
+        event_tx
+            .send(LcuEvent::ChampSelect(Some(json!({ "localPlayerCellId": 0, "stale": true }))))
+            .unwrap();
+
+        // 事件通道正常时使用推送的阶段，兜底轮询间隔内不再请求
+        flow.update(&api, &settings, false, &tx, &heroes).await.unwrap();
+        flow.wait(Duration::from_millis(10), None, &tx).await;
+        assert_eq!(flow.pushed_phase.as_deref(), Some("Lobby"));
+        flow.update(&api, &settings, false, &tx, &heroes).await.unwrap();
+        assert_eq!(flow.last_phase, "Lobby");
+        assert_eq!(phase_polls(&api), 1);
+
+        // 推送断开后丢弃旧的推送状态，下一轮重新轮询阶段
+        drop(event_tx);
+        flow.wait(Duration::from_millis(10), None, &tx).await;
+        assert!(flow.events.is_none() && flow.pushed_phase.is_none() && flow.pushed_session.is_none());
+        assert!(flow.event_retry_at.is_some());
+
+        flow.update(&api, &settings, false, &tx, &heroes).await.unwrap();
+        assert_eq!(phase_polls(&api), 2);
+        assert_eq!(flow.last_phase, "ChampSelect");
+        // 没有推送的会话，英雄选择改为请求会话
+        let requests = api.requests();
+        let polled = requests.iter().rposition(|r| r.endpoint == PHASE).unwrap();
+        assert!(requests[polled..].iter().any(|r| r.endpoint == "/lol-champ-select/v1/session"));
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::Log(ref s) if s == "事件推送断开，改为轮询")));
+    }
+
+    /// 通过 HTTPS 连接模拟客户端，从房间开始匹配，直到完成自己的禁用和选人
+    #[cfg(feature = "mock-lcu")]
+    #[tokio::test]
+    async fn mock_client_ranked_match_bans_and_picks() {
+        use crate::mock_lcu::scenario::{MockState, Scenario};
+        use crate::mock_lcu::server::serve;
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(MockState::new(Scenario::Ranked, 0)));
+        tokio::spawn(serve(listener, state, "mock"));
+
+        let api = HttpLcu {
+            client: Client::builder().danger_accept_invalid_certs(true).build().unwrap(),
+            conn: LcuConnection {
+                url: format!("https://127.0.0.1:{}", port),
+                auth_header: format!("Basic {}", BASE64_STANDARD.encode("riot:mock")),
+            },
+            recorder: None,
+        };
+        // 立即锁定；157 是模拟账号未拥有的英雄，应跳过
+        let mut settings = Settings {
+            auto_queue: true,
+            queue_delay: 0,
+            auto_lock: true,
+            lock_time: 30,
+            auto_ban_lock: true,
+            ban_time: 30,
+            teammate_lookup: false,
+            ..Default::default()
+        };
+        settings.sr_picks.insert("top".into(), vec![157, 64]);
+        settings.sr_bans.insert("top".into(), vec![238]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let heroes = Arc::new(Mutex::new(HashMap::new()));
+
+        let mut flow = FlowState::default();
+        let (mut banned, mut picked) = (false, false);
+        let deadline = Instant::now() + Duration::from_secs(40);
+        while !(banned && picked) && Instant::now() < deadline {
+            let delay = flow.update(&api, &settings, false, &tx, &heroes).await.unwrap();
+            if let Ok(session) = api.request("GET", "/lol-champ-select/v1/session", None).await {
+                banned = session["bans"]["myTeamBans"].as_array().is_some_and(|b| b.contains(&json!(238)));
+                let me = &session["actions"][1][0];
+                picked = me["actorCellId"] == 0 && me["completed"] == true && me["championId"] == 64;
+            }
+            flow.wait(delay.min(Duration::from_millis(200)), None, &tx).await;
+        }
+        assert!(banned && picked, "阶段停在 {}", flow.last_phase);
     }
 }
@@ -0,0 +1,279 @@
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::lcu::{load_heroes, LcuApi, LcuError, LcuResult};
+use crate::models::{ChampSelectState, GuiMsg, Settings};
+use crate::utils::load_settings;
+
+use super::champ_select::handle_champ_select;
+
+const SESSION_ENDPOINT: &str = "/lol-champ-select/v1/session";
+const GAMEFLOW_SESSION_ENDPOINT: &str = "/lol-gameflow/v1/session";
+/// 与后端英雄选择阶段的轮询间隔一致
+const TICK: Duration = Duration::from_millis(200);
+/// 最后一个会话快照之后继续运行的时间（让已计划的锁定得以执行）
+const TAIL: Duration = Duration::from_secs(3);
+
+/// 录制文件中解析出的内容
+struct Capture {
+    /// 录制开始时的设置，没有时使用本地设置
+    settings: Option<Settings>,
+    /// 英雄选择会话快照（推送或请求的时间, 会话）
+    snapshots: Vec<(DateTime<FixedOffset>, serde_json::Value)>,
+    /// 游戏流程会话中最后出现的队列
+    queue_id: Option<i64>,
+    /// 按顺序返回录制中其他响应
+    api: ReplayLcu,
+}
+
+/// 回放中助手发出的一次请求
+struct SentRequest {
+    method: String,
+    endpoint: String,
+    body: Option<serde_json::Value>,
+}
+
+/// 回放用的 LCU：按 `方法 + 路径` 依次返回录制的响应（最后一条会一直重复），
+/// 并记录助手发出的请求。录制中没有的路径返回空对象。
+#[derive(Default)]
+struct ReplayLcu {
+    /// `方法 路径` -> (状态码, 响应体)
+    responses: Mutex<HashMap<String, VecDeque<(u16, serde_json::Value)>>>,
+    sent: Mutex<Vec<SentRequest>>,
+}
+
+impl ReplayLcu {
+    fn push(&self, method: &str, endpoint: &str, status: u16, response: serde_json::Value) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(format!("{} {}", method, endpoint))
+            .or_default()
+            .push_back((status, response));
+    }
+
+    /// 取出并清空已发出的请求
+    fn take_sent(&self) -> Vec<SentRequest> {
+        std::mem::take(&mut *self.sent.lock().unwrap())
+    }
+}
+
+impl LcuApi for ReplayLcu {
+    async fn request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> LcuResult<serde_json::Value> {
+        self.sent.lock().unwrap().push(SentRequest {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            body,
+        });
+        let mut responses = self.responses.lock().unwrap();
+        let recorded = match responses.get_mut(&format!("{} {}", method, endpoint)) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        match recorded {
+            None => Ok(serde_json::json!({})),
+            Some((200, v)) => Ok(v),
+            // 录制的错误体格式: { "errorCode": "...", "message": "..." }
+            Some((status, v)) => Err(LcuError::Http {
+                status,
+                error_code: v["errorCode"].as_str().unwrap_or("").to_string(),
+                message: v["message"].as_str().unwrap_or("").to_string(),
+            }),
+        }
+    }
+}
+
+/// 回放录制文件中的英雄选择会话
+///
+/// 按原始时间间隔把每个会话快照交给 `handle_champ_select`，录制中的其他响应
+/// 由假 LCU 按顺序返回。输出日志和助手发出的请求，并写入 `<录制文件>.replay.txt`。
+pub async fn run_replay(path: &str) -> anyhow::Result<()> {
+    let content = fs::read_to_string(path).with_context(|| format!("无法读取 {}", path))?;
+    let Capture {
+        settings,
+        snapshots,
+        queue_id,
+        api,
+    } = load_capture(&content)?;
+    anyhow::ensure!(!snapshots.is_empty(), "录制中没有英雄选择会话");
+    // 与实际运行一致，叠加录制中队列对应的方案
+    let settings = settings.unwrap_or_else(load_settings).for_queue(queue_id);
+
+    let mut report = fs::File::create(format!("{}.replay.txt", path))?;
+    let mut out = |line: String| {
+        println!("{}", line);
+        let _ = writeln!(report, "{}", line);
+    };
+    out(format!("回放 {} 个会话快照", snapshots.len()));
+    if let Some(p) = settings.profile_for(queue_id) {
+        out(format!("队列方案: {}", p.name));
+    }
+
+    let shared_heroes = Arc::new(Mutex::new(HashMap::new()));
+    load_heroes(&shared_heroes);
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let mut champ_select = ChampSelectState::default();
+    let started = Instant::now();
+
+    for (i, (time, session)) in snapshots.iter().enumerate() {
+        let span = snapshots
+            .get(i + 1)
+            .and_then(|(next, _)| (*next - *time).to_std().ok())
+            .unwrap_or(TAIL);
+        let snapshot_start = Instant::now();
+        loop {
+            handle_champ_select(
+                &api,
+                &settings,
+                queue_id,
+                &tx,
+                Some(session),
+                &mut champ_select,
+                &shared_heroes,
+            )
+            .await;
+
+            let elapsed = started.elapsed().as_secs_f64();
+            for msg in rx.try_iter() {
+                if let GuiMsg::Log(s) = msg {
+                    out(format!("[+{:6.2}s] {}", elapsed, s));
+                }
+            }
+            for req in api.take_sent() {
+                out(format!(
+                    "[+{:6.2}s] -> {} {} {}",
+                    elapsed,
+                    req.method,
+                    req.endpoint,
+                    req.body.map(|b| b.to_string()).unwrap_or_default()
+                ));
+            }
+
+            if snapshot_start.elapsed() >= span {
+                break;
+            }
+            tokio::time::sleep(TICK.min(span.saturating_sub(snapshot_start.elapsed()))).await;
+        }
+    }
+    Ok(())
+}
+
+/// 解析录制文件（每行一条 JSON 记录，格式见 `Recorder`）
+fn load_capture(content: &str) -> anyhow::Result<Capture> {
+    let mut capture = Capture {
+        settings: None,
+        snapshots: Vec::new(),
+        queue_id: None,
+        api: ReplayLcu::default(),
+    };
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let entry: serde_json::Value = serde_json::from_str(line)?;
+        let time = entry
+            .get("time")
+            .and_then(|t| t.as_str())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+        match entry.get("kind").and_then(|k| k.as_str()).unwrap_or("") {
+            "settings" => {
+                if let Ok(s) = serde_json::from_value::<Settings>(entry["settings"].clone()) {
+                    capture.settings = Some(s);
+                }
+            }
+            "event" if entry["uri"] == SESSION_ENDPOINT && !entry["data"].is_null() => {
+                if let Some(t) = time {
+                    capture.snapshots.push((t, entry["data"].clone()));
+                }
+            }
+            "request" => {
+                let method = entry["method"].as_str().unwrap_or("GET");
+                let endpoint = entry["endpoint"].as_str().unwrap_or("");
+                let status = entry["status"].as_u64().unwrap_or(0) as u16;
+                if method == "GET" && endpoint == GAMEFLOW_SESSION_ENDPOINT && status == 200 {
+                    let queue = entry["response"]["gameData"]["queue"]["id"].as_i64();
+                    capture.queue_id = queue.or(capture.queue_id);
+                }
+                if method == "GET" && endpoint == SESSION_ENDPOINT {
+                    if let (200, Some(t)) = (status, time) {
+                        capture.snapshots.push((t, entry["response"].clone()));
+                    }
+                } else {
+                    capture.api.push(method, endpoint, status, entry["response"].clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(capture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::Recorder;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn recorded_capture_loads_back_for_replay() {
+        let path = std::env::temp_dir().join(format!("hextech-capture-{}.jsonl", std::process::id()));
+        let settings = Settings {
+            lock_time: 7,
+            ..Default::default()
+        };
+        let session = json!({ "localPlayerCellId": 0, "benchEnabled": true });
+        {
+            let rec = Recorder::create_at(path.clone(), &settings).unwrap();
+            rec.record_request(
+                "GET",
+                GAMEFLOW_SESSION_ENDPOINT,
+                None,
+                &Ok(json!({ "gameData": { "queue": { "id": 450 } } })),
+            );
+            rec.record_request("GET", SESSION_ENDPOINT, None, &Ok(session.clone()));
+            rec.record_event(SESSION_ENDPOINT, Some(&session));
+            // 会话结束的推送没有数据，不作为快照
+            rec.record_event(SESSION_ENDPOINT, None);
+            rec.record_request(
+                "GET",
+                "/lol-champ-select/v1/pickable-champion-ids",
+                None,
+                &Ok(json!([1, 2, 3])),
+            );
+            rec.record_request(
+                "PATCH",
+                "/lol-champ-select/v1/session/actions/1",
+                Some(&json!({ "championId": 1 })),
+                &Err(LcuError::Http {
+                    status: 500,
+                    error_code: "RPC_ERROR".into(),
+                    message: "Invalid action".into(),
+                }),
+            );
+        }
+
+        let capture = load_capture(&fs::read_to_string(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(capture.settings.map(|s| s.lock_time), Some(7));
+        assert_eq!(capture.queue_id, Some(450));
+        assert_eq!(capture.snapshots.len(), 2);
+        assert!(capture.snapshots.iter().all(|(_, s)| *s == session));
+        assert!(capture.snapshots[0].0 <= capture.snapshots[1].0);
+        let pickable = capture.api.request("GET", "/lol-champ-select/v1/pickable-champion-ids", None).await;
+        assert_eq!(pickable.unwrap(), json!([1, 2, 3]));
+        let patch = capture.api.request("PATCH", "/lol-champ-select/v1/session/actions/1", None).await;
+        assert!(matches!(patch, Err(LcuError::Http { status: 500, .. })));
+        let sent: Vec<_> = capture.api.take_sent().into_iter().map(|r| r.method).collect();
+        assert_eq!(sent, ["GET", "PATCH"]);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::{LcuApi, LcuResult};
+use crate::models::{GuiMsg, Hero, RunePreset};
+use crate::utils::{load_rune_presets, lookup_hero_name_by_id};
+
+/// 助手创建的符文页名称前缀，只会覆盖带此前缀的页
+const MANAGED_PAGE_PREFIX: &str = "HEX: ";
+
+/// 查找英雄的符文预设：先匹配位置，再用不限位置的预设
+fn find_preset<'a>(presets: &'a [RunePreset], champ_id: i32, position: &str) -> Option<&'a RunePreset> {
+    let for_champ = || presets.iter().filter(move |p| p.champion_id == champ_id);
+    for_champ()
+        .find(|p| p.position == position)
+        .or_else(|| for_champ().find(|p| p.position.is_empty()))
+}
+
+/// 相同英雄和位置的预设只保留一份
+pub fn upsert_preset(presets: &mut Vec<RunePreset>, preset: RunePreset) {
+    match presets
+        .iter_mut()
+        .find(|p| p.champion_id == preset.champion_id && p.position == preset.position)
+    {
+        Some(p) => *p = preset,
+        None => presets.push(preset),
+    }
+}
+
+/// 我方英雄确定后应用符文预设，返回 false 表示请求失败（稍后重试）
+pub async fn apply_runes(
+    api: &impl LcuApi,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    champ_id: i32,
+    position: &str,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> bool {
+    let presets = load_rune_presets();
+    let Some(preset) = find_preset(&presets, champ_id, position) else {
+        return true;
+    };
+    let name = if preset.name.is_empty() {
+        lookup_hero_name_by_id(shared_heroes, champ_id)
+    } else {
+        preset.name.clone()
+    };
+
+    let page_name = format!("{}{}", MANAGED_PAGE_PREFIX, name);
+    let msg = match write_managed_page(api, preset, &page_name).await {
+        Ok(true) => format!("已应用符文: {}", name),
+        Ok(false) => "符文页已满，未应用符文预设".to_string(),
+        Err(e) => {
+            tx.send(GuiMsg::Log(format!("应用符文失败: {}", e))).ok();
+            return false;
+        }
+    };
+    tx.send(GuiMsg::Log(msg)).ok();
+    true
+}
+
+/// 写入助手管理的符文页并设为当前页，返回 false 表示没有空余的符文页
+///
+/// 已有管理页时直接覆盖（多余的管理页随后删除），没有时新建；不会动用户自己的符文页。
+async fn write_managed_page(api: &impl LcuApi, preset: &RunePreset, name: &str) -> LcuResult<bool> {
+    let pages = api.request("GET", "/lol-perks/v1/pages", None).await?;
+    let editable: Vec<&serde_json::Value> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| p.get("isDeletable").and_then(|v| v.as_bool()).unwrap_or(false))
+        .collect();
+    let managed: Vec<i64> = editable
+        .iter()
+        .filter(|p| {
+            p.get("name")
+                .and_then(|v| v.as_str())
+                .is_some_and(|n| n.starts_with(MANAGED_PAGE_PREFIX))
+        })
+        .filter_map(|p| p.get("id").and_then(|v| v.as_i64()))
+        .collect();
+
+    let mut body = serde_json::json!({
+        "name": name,
+        "primaryStyleId": preset.primary_style_id,
+        "subStyleId": preset.sub_style_id,
+        "selectedPerkIds": preset.selected_perk_ids,
+        "current": true,
+    });
+    let id = match managed.split_first() {
+        Some((&id, extra)) => {
+            body["id"] = serde_json::json!(id);
+            api.request("PUT", &format!("/lol-perks/v1/pages/{}", id), Some(body)).await?;
+            for old in extra {
+                api.request("DELETE", &format!("/lol-perks/v1/pages/{}", old), None).await?;
+            }
+            Some(id)
+        }
+        None => {
+            let inventory = api.request("GET", "/lol-perks/v1/inventory", None).await?;
+            if let Some(owned) = inventory.get("ownedPageCount").and_then(|v| v.as_u64()) {
+                if editable.len() as u64 >= owned {
+                    return Ok(false);
+                }
+            }
+            let page = api.request("POST", "/lol-perks/v1/pages", Some(body)).await?;
+            page.get("id").and_then(|v| v.as_i64())
+        }
+    };
+    if let Some(id) = id {
+        api.request("PUT", "/lol-perks/v1/currentpage", Some(serde_json::json!(id))).await?;
+    }
+    Ok(true)
+}
+
+/// 读取客户端当前符文页，生成该英雄/位置的预设
+pub async fn import_current_page(
+    api: &impl LcuApi,
+    champion_id: i32,
+    position: String,
+) -> LcuResult<RunePreset> {
+    let page = api.request("GET", "/lol-perks/v1/currentpage", None).await?;
+    let name = page.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    Ok(RunePreset {
+        champion_id,
+        position,
+        name: name.trim_start_matches(MANAGED_PAGE_PREFIX).to_string(),
+        primary_style_id: page.get("primaryStyleId").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+        sub_style_id: page.get("subStyleId").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+        selected_perk_ids: page
+            .get("selectedPerkIds")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|x| x.as_i64()).map(|x| x as i32).collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn preset() -> RunePreset {
+        RunePreset {
+            champion_id: 103,
+            position: "middle".into(),
+            name: "阿狸".into(),
+            primary_style_id: 8100,
+            sub_style_id: 8200,
+            selected_perk_ids: vec![8112, 8139, 8138, 8135, 8226, 8210, 5008, 5008, 5001],
+        }
+    }
+
+    #[test]
+    fn position_specific_preset_wins() {
+        let any_role = RunePreset {
+            position: String::new(),
+            name: "通用".into(),
+            ..preset()
+        };
+        let presets = vec![any_role, preset()];
+        assert_eq!(find_preset(&presets, 103, "middle").unwrap().name, "阿狸");
+        assert_eq!(find_preset(&presets, 103, "top").unwrap().name, "通用");
+        assert!(find_preset(&presets, 1, "middle").is_none());
+    }
+
+    #[tokio::test]
+    async fn overwrites_managed_page_only() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-perks/v1/pages",
+            [json!([
+                { "id": 1, "name": "我的符文", "isDeletable": true },
+                { "id": 2, "name": "HEX: 亚索", "isDeletable": true },
+                { "id": 4, "name": "HEX: 劫", "isDeletable": true },
+                { "id": 50, "name": "推荐", "isDeletable": false }
+            ])],
+        );
+
+        assert!(write_managed_page(&api, &preset(), "HEX: 阿狸").await.unwrap());
+
+        let reqs = api.requests();
+        let lines: Vec<String> = reqs.iter().map(|r| format!("{} {}", r.method, r.endpoint)).collect();
+        assert_eq!(
+            lines,
+            [
+                "GET /lol-perks/v1/pages",
+                "PUT /lol-perks/v1/pages/2",
+                "DELETE /lol-perks/v1/pages/4",
+                "PUT /lol-perks/v1/currentpage",
+            ]
+        );
+        let body = reqs[1].body.as_ref().unwrap();
+        assert_eq!((body["id"].clone(), body["name"].clone()), (json!(2), json!("HEX: 阿狸")));
+    }
+
+    #[tokio::test]
+    async fn creates_page_when_none_is_managed() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-perks/v1/pages",
+            [json!([{ "id": 1, "name": "我的符文", "isDeletable": true }])],
+        );
+        api.script("GET", "/lol-perks/v1/inventory", [json!({ "ownedPageCount": 2 })]);
+        api.script("POST", "/lol-perks/v1/pages", [json!({ "id": 3 })]);
+
+        assert!(write_managed_page(&api, &preset(), "HEX: 阿狸").await.unwrap());
+
+        let reqs = api.requests();
+        assert_eq!(reqs[2].method, "POST");
+        assert!(reqs.iter().all(|r| r.method != "DELETE"));
+        assert_eq!(reqs.last().unwrap().body, Some(json!(3)));
+    }
+
+    #[tokio::test]
+    async fn full_inventory_leaves_pages_alone() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-perks/v1/pages",
+            [json!([{ "id": 1, "name": "我的符文", "isDeletable": true }])],
+        );
+        api.script("GET", "/lol-perks/v1/inventory", [json!({ "ownedPageCount": 1 })]);
+
+        assert!(!write_managed_page(&api, &preset(), "HEX: 阿狸").await.unwrap());
+        assert_eq!(api.requests().len(), 2);
+    }
+}
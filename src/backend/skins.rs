@@ -0,0 +1,175 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::lcu::LcuApi;
+use crate::models::{GuiMsg, Settings};
+
+/// 可选的皮肤（含炫彩）
+struct OwnedSkin {
+    id: i32,
+    name: String,
+    /// 炫彩属于哪个皮肤，基础皮肤为 None
+    parent: Option<i32>,
+}
+
+/// 读取皮肤列表并按设置选择皮肤，返回 false 表示列表还不是该英雄的（稍后重试）
+///
+/// 英雄的收藏列表中第一个已拥有的皮肤优先，其次是随机的已拥有皮肤（不含炫彩和默认皮肤）。
+pub async fn apply_skin(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    me: &serde_json::Value,
+    champ_id: i32,
+) -> bool {
+    let carousel = match api
+        .request("GET", "/lol-champ-select/v1/skin-carousel-skins", None)
+        .await
+    {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+    let skins = carousel.as_array().cloned().unwrap_or_default();
+    let for_champ = skins
+        .first()
+        .and_then(|s| s.get("championId"))
+        .and_then(|v| v.as_i64())
+        == Some(champ_id as i64);
+    if !for_champ {
+        return false;
+    }
+
+    let owned = owned_skins(&skins);
+    tx.send(GuiMsg::SkinCarousel {
+        champion_id: champ_id,
+        skins: owned.iter().map(|s| (s.id, s.name.clone())).collect(),
+    }).ok();
+    if !settings.auto_skin {
+        return true;
+    }
+
+    let favourites = settings.favourite_skins.get(&champ_id).map(Vec::as_slice).unwrap_or(&[]);
+    let Some(skin) = choose_skin(&owned, favourites, settings.random_skin, champ_id) else {
+        return true;
+    };
+    if me.get("selectedSkinId").and_then(|v| v.as_i64()) == Some(skin.id as i64) {
+        return true;
+    }
+
+    let body = serde_json::json!({ "selectedSkinId": skin.id });
+    match api
+        .request("PATCH", "/lol-champ-select/v1/session/my-selection", Some(body))
+        .await
+    {
+        Ok(_) => tx.send(GuiMsg::Log(format!("皮肤: {}", skin.name))).ok(),
+        Err(e) => tx.send(GuiMsg::Log(format!("设置皮肤失败: {}", e))).ok(),
+    };
+    true
+}
+
+/// 已拥有且可用的皮肤与炫彩
+fn owned_skins(carousel: &[serde_json::Value]) -> Vec<OwnedSkin> {
+    let usable = |s: &serde_json::Value| {
+        s.get("unlocked").and_then(|v| v.as_bool()).unwrap_or(false)
+            && !s.get("disabled").and_then(|v| v.as_bool()).unwrap_or(false)
+    };
+    let skin = |s: &serde_json::Value, parent: Option<i32>| OwnedSkin {
+        id: s.get("id").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+        name: s.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        parent,
+    };
+
+    let mut owned = Vec::new();
+    for s in carousel.iter().filter(|s| usable(s)) {
+        let base = skin(s, None);
+        let base_id = base.id;
+        owned.push(base);
+        let chromas = s.get("childSkins").and_then(|v| v.as_array());
+        for c in chromas.into_iter().flatten().filter(|c| usable(c)) {
+            owned.push(skin(c, Some(base_id)));
+        }
+    }
+    owned
+}
+
+fn choose_skin<'a>(
+    owned: &'a [OwnedSkin],
+    favourites: &[i32],
+    random: bool,
+    champ_id: i32,
+) -> Option<&'a OwnedSkin> {
+    if let Some(fav) = favourites
+        .iter()
+        .find_map(|id| owned.iter().find(|s| s.id == *id))
+    {
+        return Some(fav);
+    }
+    if !random {
+        return None;
+    }
+    // 默认皮肤ID为 英雄ID * 1000
+    let pool: Vec<&OwnedSkin> = owned
+        .iter()
+        .filter(|s| s.parent.is_none() && s.id != champ_id * 1000)
+        .collect();
+    if pool.is_empty() {
+        return None;
+    }
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as usize)
+        .unwrap_or(0);
+    Some(pool[seed % pool.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn carousel() -> serde_json::Value {
+        json!([
+            { "id": 103000, "championId": 103, "name": "阿狸", "unlocked": true, "childSkins": [] },
+            { "id": 103001, "championId": 103, "name": "王朝阿狸", "unlocked": false, "childSkins": [] },
+            { "id": 103015, "championId": 103, "name": "星之守护者 阿狸", "unlocked": true, "childSkins": [
+                { "id": 103016, "championId": 103, "name": "星之守护者 阿狸 (红宝石)", "unlocked": true }
+            ] }
+        ])
+    }
+
+    #[tokio::test]
+    async fn favourite_chroma_is_applied_when_owned() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-champ-select/v1/skin-carousel-skins", [carousel()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut s = Settings {
+            auto_skin: true,
+            ..Default::default()
+        };
+        s.favourite_skins.insert(103, vec![103001, 103016]);
+
+        assert!(apply_skin(&api, &s, &tx, &json!({ "selectedSkinId": 103000 }), 103).await);
+
+        let patch = api.requests().into_iter().find(|r| r.method == "PATCH").unwrap();
+        assert_eq!(patch.body, Some(json!({ "selectedSkinId": 103016 })));
+    }
+
+    #[test]
+    fn random_skin_skips_default_and_chromas() {
+        let owned = owned_skins(carousel().as_array().unwrap());
+        assert_eq!(owned.len(), 3);
+        for _ in 0..5 {
+            assert_eq!(choose_skin(&owned, &[], true, 103).unwrap().id, 103015);
+        }
+        assert!(choose_skin(&owned, &[], false, 103).is_none());
+    }
+
+    #[tokio::test]
+    async fn waits_for_carousel_of_new_champion() {
+        let api = FakeLcu::new();
+        api.script("GET", "/lol-champ-select/v1/skin-carousel-skins", [carousel()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        assert!(!apply_skin(&api, &Settings::default(), &tx, &json!({}), 222).await);
+    }
+}
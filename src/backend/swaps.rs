@@ -0,0 +1,269 @@
+use crate::lcu::LcuApi;
+use crate::models::{ChampSelectState, GuiMsg, Settings, SwapPolicy};
+
+/// 选人顺序交换与位置交换
+#[derive(Clone, Copy)]
+enum SwapKind {
+    PickOrder,
+    Position,
+}
+
+impl SwapKind {
+    /// 会话中的字段
+    fn session_key(self) -> &'static str {
+        match self {
+            SwapKind::PickOrder => "pickOrderSwaps",
+            SwapKind::Position => "positionSwaps",
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            SwapKind::PickOrder => "pick-order-swaps",
+            SwapKind::Position => "position-swaps",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SwapKind::PickOrder => "交换选人顺序",
+            SwapKind::Position => "交换位置",
+        }
+    }
+
+    fn policy(self, settings: &Settings) -> SwapPolicy {
+        match self {
+            SwapKind::PickOrder => settings.pick_order_swap,
+            SwapKind::Position => settings.position_swap,
+        }
+    }
+}
+
+/// 按设置处理收到的选人顺序交换和位置交换请求
+///
+/// 交换完成后自己的 action 会变化，清除未完成 action 的处理状态，
+/// 让新的 action 重新预选并计划锁定。
+pub async fn handle_swaps(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    cs: &mut ChampSelectState,
+) {
+    for kind in [SwapKind::PickOrder, SwapKind::Position] {
+        let Some(swaps) = session.get(kind.session_key()).and_then(|v| v.as_array()) else {
+            continue;
+        };
+        for swap in swaps {
+            let id = swap.get("id").and_then(|v| v.as_i64()).unwrap_or(-1);
+            let cell = swap.get("cellId").and_then(|v| v.as_i64()).unwrap_or(-1);
+            let state = swap.get("state").and_then(|v| v.as_str()).unwrap_or("");
+            let key = format!("{}/{}", kind.endpoint(), id);
+            let prev = cs.swap_states.insert(key, state.to_string());
+            if prev.as_deref() == Some(state) {
+                continue;
+            }
+
+            match state {
+                "RECEIVED" => {
+                    tx.send(GuiMsg::Log(format!("收到{}请求 ({}号位)", kind.label(), cell))).ok();
+                    let accept = match kind.policy(settings) {
+                        SwapPolicy::Manual => continue,
+                        SwapPolicy::Accept => true,
+                        SwapPolicy::Decline => false,
+                        SwapPolicy::Smart => match kind {
+                            SwapKind::PickOrder => moves_earlier(session, local_cell_id, cell),
+                            SwapKind::Position => has_presets(settings, &position_of(session, cell)),
+                        },
+                    };
+                    let action = if accept { "accept" } else { "decline" };
+                    let endpoint = format!(
+                        "/lol-champ-select/v1/session/{}/{}/{}",
+                        kind.endpoint(),
+                        id,
+                        action
+                    );
+                    let what = if accept { "接受" } else { "拒绝" };
+                    match api.request("POST", &endpoint, None).await {
+                        Ok(_) => tx.send(GuiMsg::Log(format!("自动{}{}", what, kind.label()))).ok(),
+                        Err(e) => tx
+                            .send(GuiMsg::Log(format!("{}{}失败: {}", what, kind.label(), e)))
+                            .ok(),
+                    };
+                }
+                "ACCEPTED" => {
+                    tx.send(GuiMsg::Log(format!("{}已完成", kind.label()))).ok();
+                    cs.handled_actions.retain(|_, s| s.completed);
+                }
+                "DECLINED" => {
+                    tx.send(GuiMsg::Log(format!("{}被拒绝", kind.label()))).ok();
+                }
+                "CANCELLED" => {
+                    tx.send(GuiMsg::Log(format!("{}已取消", kind.label()))).ok();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 该格子的第一个选人 action 所在的轮次，没有时返回 None
+fn pick_turn(session: &serde_json::Value, cell: i64) -> Option<usize> {
+    session
+        .get("actions")
+        .and_then(|v| v.as_array())?
+        .iter()
+        .position(|group| {
+            group.as_array().is_some_and(|list| {
+                list.iter().any(|a| {
+                    a.get("actorCellId").and_then(|v| v.as_i64()) == Some(cell)
+                        && a.get("type").and_then(|v| v.as_str()) == Some("pick")
+                })
+            })
+        })
+}
+
+/// 与对方交换后自己是否更早选人
+fn moves_earlier(session: &serde_json::Value, local_cell_id: i64, other: i64) -> bool {
+    match (pick_turn(session, local_cell_id), pick_turn(session, other)) {
+        (Some(mine), Some(theirs)) => theirs < mine,
+        _ => false,
+    }
+}
+
+fn position_of(session: &serde_json::Value, cell: i64) -> String {
+    session
+        .get("myTeam")
+        .and_then(|v| v.as_array())
+        .and_then(|tm| {
+            tm.iter()
+                .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(cell))
+        })
+        .and_then(|m| m.get("assignedPosition"))
+        .and_then(|s| s.as_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// 该位置是否配置了预选英雄
+fn has_presets(settings: &Settings, position: &str) -> bool {
+    settings.sr_picks.get(position).is_some_and(|l| !l.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use crate::models::ActionState;
+    use serde_json::json;
+
+    /// 自己在 2 号格子（中路，第三轮选人），1 号格子上单第一轮选人，3 号格子打野第四轮
+    fn session(key: &str, from_cell: i64, state: &str) -> serde_json::Value {
+        let mut session = json!({
+            "localPlayerCellId": 2,
+            "myTeam": [
+                { "cellId": 1, "assignedPosition": "top" },
+                { "cellId": 2, "assignedPosition": "middle" },
+                { "cellId": 3, "assignedPosition": "jungle" }
+            ],
+            "actions": [
+                [{ "id": 1, "actorCellId": 1, "type": "pick" }],
+                [{ "id": 2, "actorCellId": 6, "type": "pick" }],
+                [{ "id": 3, "actorCellId": 2, "type": "pick" }],
+                [{ "id": 4, "actorCellId": 3, "type": "pick" }]
+            ]
+        });
+        session[key] = json!([{ "id": 5, "cellId": from_cell, "state": state }]);
+        session
+    }
+
+    fn posts(api: &FakeLcu) -> Vec<String> {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| r.endpoint)
+            .collect()
+    }
+
+    fn smart() -> Settings {
+        let mut s = Settings {
+            pick_order_swap: SwapPolicy::Smart,
+            position_swap: SwapPolicy::Smart,
+            ..Default::default()
+        };
+        s.sr_picks.insert("jungle".into(), vec![64]);
+        s
+    }
+
+    #[tokio::test]
+    async fn smart_policy_accepts_earlier_pick_and_declines_later() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        let api = FakeLcu::new();
+        let s = session("pickOrderSwaps", 1, "RECEIVED");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut ChampSelectState::default()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/pick-order-swaps/5/accept"]);
+
+        let api = FakeLcu::new();
+        let s = session("pickOrderSwaps", 3, "RECEIVED");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut ChampSelectState::default()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/pick-order-swaps/5/decline"]);
+    }
+
+    #[tokio::test]
+    async fn smart_policy_accepts_positions_with_presets_only() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        let api = FakeLcu::new();
+        let s = session("positionSwaps", 3, "RECEIVED");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut ChampSelectState::default()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/position-swaps/5/accept"]);
+
+        let api = FakeLcu::new();
+        let s = session("positionSwaps", 1, "RECEIVED");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut ChampSelectState::default()).await;
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/position-swaps/5/decline"]);
+    }
+
+    #[tokio::test]
+    async fn manual_policy_only_logs() {
+        let api = FakeLcu::new();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let s = session("pickOrderSwaps", 1, "RECEIVED");
+
+        handle_swaps(&api, &Settings::default(), &tx, &s, 2, &mut ChampSelectState::default()).await;
+
+        assert!(posts(&api).is_empty());
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::Log(ref l) if l.contains("交换选人顺序"))));
+    }
+
+    #[tokio::test]
+    async fn accepted_swap_resets_pending_actions() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+        cs.handled_actions.insert(
+            3,
+            ActionState {
+                hovered: true,
+                ..Default::default()
+            },
+        );
+        cs.handled_actions.insert(
+            9,
+            ActionState {
+                completed: true,
+                ..Default::default()
+            },
+        );
+
+        let s = session("pickOrderSwaps", 1, "SENT");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut cs).await;
+        assert_eq!(cs.handled_actions.len(), 2);
+
+        let s = session("pickOrderSwaps", 1, "ACCEPTED");
+        handle_swaps(&api, &smart(), &tx, &s, 2, &mut cs).await;
+        assert_eq!(cs.handled_actions.keys().collect::<Vec<_>>(), vec![&9]);
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use crate::lcu::LcuApi;
+use crate::models::{GuiMsg, TeammateInfo};
+use crate::utils::solo_rank_label;
+
+/// 查询队友的段位与近期战绩并发送给界面
+///
+/// 在后台任务中运行，不阻塞英雄选择的锁定逻辑。
+pub async fn handle_teammates(
+    api: impl LcuApi,
+    tx: crossbeam_channel::Sender<GuiMsg>,
+    games: usize,
+) {
+    let Ok(session) = api.request("GET", "/lol-champ-select/v1/session", None).await else {
+        return;
+    };
+    let teammates = lookup_teammates(&api, &session, games).await;
+    if !teammates.is_empty() {
+        tx.send(GuiMsg::Log(format!("已查询 {} 名队友的战绩", teammates.len()))).ok();
+    }
+    tx.send(GuiMsg::Teammates(teammates)).ok();
+}
+
+/// 根据会话中 myTeam 的 puuid/summonerId 逐个查询队友（不含自己）
+pub async fn lookup_teammates(
+    api: &impl LcuApi,
+    session: &serde_json::Value,
+    games: usize,
+) -> Vec<TeammateInfo> {
+    let local_cell_id = session
+        .get("localPlayerCellId")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(-1);
+    let members = session.get("myTeam").and_then(|v| v.as_array());
+    let mut result = Vec::new();
+    for m in members.into_iter().flatten() {
+        let cell_id = m.get("cellId").and_then(|v| v.as_i64()).unwrap_or(-1);
+        if cell_id == local_cell_id {
+            continue;
+        }
+        let mut info = TeammateInfo {
+            cell_id,
+            position: m
+                .get("assignedPosition")
+                .and_then(|s| s.as_str())
+                .unwrap_or("")
+                .to_lowercase(),
+            ..Default::default()
+        };
+
+        let mut puuid = m.get("puuid").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let summoner_id = m.get("summonerId").and_then(|v| v.as_i64()).unwrap_or(0);
+        if summoner_id > 0 {
+            let endpoint = format!("/lol-summoner/v1/summoners/{}", summoner_id);
+            if let Ok(summoner) = api.request("GET", &endpoint, None).await {
+                info.name = summoner_name(&summoner);
+                if puuid.is_empty() {
+                    puuid = summoner["puuid"].as_str().unwrap_or("").to_string();
+                }
+            }
+        }
+        // 排位中的匿名队友没有 puuid
+        if puuid.is_empty() {
+            info.hidden = true;
+            result.push(info);
+            continue;
+        }
+
+        match api.request("GET", &format!("/lol-ranked/v1/ranked-stats/{}", puuid), None).await {
+            Ok(stats) => {
+                info.rank = solo_rank_label(&stats);
+                let solo = &stats["queueMap"]["RANKED_SOLO_5x5"];
+                info.ranked_wins = solo["wins"].as_i64().unwrap_or(0);
+                info.ranked_losses = solo["losses"].as_i64().unwrap_or(0);
+            }
+            Err(_) => info.hidden = true,
+        }
+
+        let endpoint = format!(
+            "/lol-match-history/v1/products/lol/{}/matches?begIndex=0&endIndex={}",
+            puuid, games
+        );
+        match api.request("GET", &endpoint, None).await {
+            Ok(history) => apply_recent_games(&mut info, &history, &puuid, games),
+            Err(_) => info.hidden = true,
+        }
+        result.push(info);
+    }
+    result
+}
+
+/// "名称#标签"，旧账号没有 Riot ID 时使用召唤师名
+fn summoner_name(summoner: &serde_json::Value) -> String {
+    let game_name = summoner["gameName"].as_str().unwrap_or("");
+    let tag = summoner["tagLine"].as_str().unwrap_or("");
+    match (game_name, tag) {
+        ("", _) => summoner["displayName"].as_str().unwrap_or("").to_string(),
+        (n, "") => n.to_string(),
+        (n, t) => format!("{}#{}", n, t),
+    }
+}
+
+/// 统计最近的对局：胜率、常用英雄与连胜/连败（对局按时间从新到旧）
+fn apply_recent_games(
+    info: &mut TeammateInfo,
+    history: &serde_json::Value,
+    puuid: &str,
+    games: usize,
+) {
+    let list = history["games"]["games"].as_array();
+    let mut champion_games: HashMap<i32, usize> = HashMap::new();
+    let mut streak_open = true;
+    for game in list.into_iter().flatten().take(games) {
+        let Some((champion, win)) = player_result(game, puuid) else {
+            continue;
+        };
+        info.games += 1;
+        if win {
+            info.recent_wins += 1;
+        }
+        *champion_games.entry(champion).or_default() += 1;
+
+        if streak_open {
+            match (info.streak.signum(), win) {
+                (0, true) | (1, true) => info.streak += 1,
+                (0, false) | (-1, false) => info.streak -= 1,
+                _ => streak_open = false,
+            }
+        }
+    }
+
+    let mut mains: Vec<(i32, usize)> = champion_games.into_iter().collect();
+    mains.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    info.main_champions = mains.into_iter().take(3).map(|(c, _)| c).collect();
+}
+
+/// 对局中该玩家使用的英雄与胜负
+///
+/// 个人战绩中通常只有玩家自己的数据，找不到对应身份时取第一个参与者。
+fn player_result(game: &serde_json::Value, puuid: &str) -> Option<(i32, bool)> {
+    let participant_id = game["participantIdentities"]
+        .as_array()
+        .and_then(|ids| ids.iter().find(|p| p["player"]["puuid"].as_str() == Some(puuid)))
+        .and_then(|p| p["participantId"].as_i64());
+    let participants = game["participants"].as_array()?;
+    let participant = participant_id
+        .and_then(|id| participants.iter().find(|p| p["participantId"].as_i64() == Some(id)))
+        .or_else(|| participants.first())?;
+    let champion = participant["championId"].as_i64()? as i32;
+    let win = participant["stats"]["win"].as_bool().unwrap_or(false);
+    Some((champion, win))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn game(champion: i32, win: bool) -> serde_json::Value {
+        json!({
+            "participantIdentities": [{ "participantId": 1, "player": { "puuid": "p1" } }],
+            "participants": [{ "participantId": 1, "championId": champion, "stats": { "win": win } }]
+        })
+    }
+
+    #[test]
+    fn recent_games_give_win_rate_mains_and_streak() {
+        let history = json!({ "games": { "games": [
+            game(103, true), game(103, true), game(64, false), game(103, true), game(7, false)
+        ] } });
+        let mut info = TeammateInfo::default();
+
+        apply_recent_games(&mut info, &history, "p1", 20);
+
+        assert_eq!((info.games, info.recent_wins), (5, 3));
+        assert_eq!(info.main_champions, vec![103, 7, 64]);
+        assert_eq!(info.streak, 2);
+    }
+
+    #[tokio::test]
+    async fn looks_up_teammates_but_not_self() {
+        let api = FakeLcu::new();
+        api.script(
+            "GET",
+            "/lol-summoner/v1/summoners/1001",
+            [json!({ "gameName": "Ally", "tagLine": "CN1", "puuid": "p1" })],
+        );
+        api.script(
+            "GET",
+            "/lol-ranked/v1/ranked-stats/p1",
+            [json!({ "queueMap": { "RANKED_SOLO_5x5": {
+                "tier": "GOLD", "division": "II", "wins": 30, "losses": 20
+            } } })],
+        );
+        api.script(
+            "GET",
+            "/lol-match-history/v1/products/lol/p1/matches?begIndex=0&endIndex=2",
+            [json!({ "games": { "games": [game(103, false), game(103, false)] } })],
+        );
+        let session = json!({
+            "localPlayerCellId": 0,
+            "myTeam": [
+                { "cellId": 0, "puuid": "me", "summonerId": 1000 },
+                { "cellId": 1, "assignedPosition": "JUNGLE", "puuid": "", "summonerId": 1001 },
+                { "cellId": 2, "puuid": "", "summonerId": 0 }
+            ]
+        });
+
+        let teammates = lookup_teammates(&api, &session, 2).await;
+
+        assert_eq!(teammates.len(), 2);
+        let ally = &teammates[0];
+        assert_eq!((ally.name.as_str(), ally.position.as_str()), ("Ally#CN1", "jungle"));
+        assert_eq!((ally.rank.as_str(), ally.ranked_wins, ally.ranked_losses), ("荣耀黄金 II", 30, 20));
+        assert_eq!((ally.games, ally.streak, ally.main_champions.clone()), (2, -2, vec![103]));
+        assert!(!ally.hidden);
+        assert!(teammates[1].hidden);
+        assert!(api.requests().iter().all(|r| !r.endpoint.contains("/me")));
+    }
+}
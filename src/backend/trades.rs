@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::LcuApi;
+use crate::models::{ChampSelectState, GuiMsg, Hero, Settings};
+use crate::utils::lookup_hero_name_by_id;
+
+/// 英雄交换处理
+///
+/// 以自己的预设列表（大乱斗为秒选列表，峡谷/竞技场为当前位置的预选列表）判断优先级：
+/// - 收到的交换：对方英雄优先级更高时接受；自己的英雄在列表中而对方更低时拒绝，
+///   两者都不在列表中时交给玩家决定
+/// - 可选向持有更想玩英雄的队友发起交换，每个队友只请求一次
+///
+/// 所有交换状态变化都会写入日志。
+pub async fn handle_trades(
+    api: &impl LcuApi,
+    settings: &Settings,
+    tx: &crossbeam_channel::Sender<GuiMsg>,
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    cs: &mut ChampSelectState,
+    shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) {
+    let Some(trades) = session.get("trades").and_then(|v| v.as_array()) else {
+        return;
+    };
+    if trades.is_empty() {
+        return;
+    }
+    let team = session.get("myTeam").and_then(|v| v.as_array());
+    let champion_of = |cell: i64| {
+        team.into_iter()
+            .flatten()
+            .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(cell))
+            .and_then(|m| m.get("championId"))
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0) as i32
+    };
+    let mine = champion_of(local_cell_id);
+    if mine <= 0 {
+        return;
+    }
+    let preferred = preferred_champions(settings, session, local_cell_id, cs.arena);
+    let rank = |champ: i32| preferred.iter().position(|&p| p == champ);
+    let name = |champ: i32| lookup_hero_name_by_id(shared_heroes, champ);
+
+    let mut busy = false;
+    let mut best_request: Option<(usize, i64, i32)> = None;
+    for t in trades {
+        let id = t.get("id").and_then(|v| v.as_i64()).unwrap_or(-1);
+        let state = t.get("state").and_then(|v| v.as_str()).unwrap_or("");
+        let theirs = champion_of(t.get("cellId").and_then(|v| v.as_i64()).unwrap_or(-1));
+        busy |= matches!(state, "SENT" | "RECEIVED");
+
+        // 可以发起交换的队友中，英雄优先级最高的一个
+        if state == "AVAILABLE" && !cs.requested_trades.contains(&id) {
+            if let Some(r) = rank(theirs).filter(|&r| rank(mine).is_none_or(|m| r < m)) {
+                if best_request.is_none_or(|(best, _, _)| r < best) {
+                    best_request = Some((r, id, theirs));
+                }
+            }
+        }
+
+        let prev = cs.trade_states.insert(id, state.to_string());
+        if prev.as_deref() == Some(state) {
+            continue;
+        }
+
+        match state {
+            "RECEIVED" => {
+                tx.send(GuiMsg::Log(format!(
+                    "收到交换请求: 用 {} 换你的 {}",
+                    name(theirs),
+                    name(mine)
+                ))).ok();
+                let action = match trade_decision(rank(theirs), rank(mine)) {
+                    Some(true) if settings.trade_accept => "accept",
+                    Some(false) if settings.trade_decline => "decline",
+                    _ => continue,
+                };
+                let endpoint = format!("/lol-champ-select/v1/session/trades/{}/{}", id, action);
+                let what = if action == "accept" { "接受交换" } else { "拒绝交换" };
+                match api.request("POST", &endpoint, None).await {
+                    Ok(_) => tx.send(GuiMsg::Log(format!("自动{}: {}", what, name(theirs)))).ok(),
+                    Err(e) => tx.send(GuiMsg::Log(format!("{}失败: {}", what, e))).ok(),
+                };
+            }
+            "SENT" => {
+                tx.send(GuiMsg::Log(format!(
+                    "已发送交换请求: 用 {} 换 {}",
+                    name(mine),
+                    name(theirs)
+                ))).ok();
+            }
+            "ACCEPTED" => {
+                tx.send(GuiMsg::Log("交换已完成".to_string())).ok();
+            }
+            "DECLINED" => {
+                tx.send(GuiMsg::Log("交换被拒绝".to_string())).ok();
+            }
+            "CANCELLED" => {
+                tx.send(GuiMsg::Log("交换已取消".to_string())).ok();
+            }
+            _ => {}
+        }
+    }
+
+    if !settings.trade_request || busy {
+        return;
+    }
+    if let Some((_, id, theirs)) = best_request {
+        cs.requested_trades.insert(id);
+        let endpoint = format!("/lol-champ-select/v1/session/trades/{}/request", id);
+        if let Err(e) = api.request("POST", &endpoint, None).await {
+            tx.send(GuiMsg::Log(format!("请求交换 {} 失败: {}", name(theirs), e))).ok();
+        }
+    }
+}
+
+/// 自己的英雄优先级列表
+fn preferred_champions(
+    settings: &Settings,
+    session: &serde_json::Value,
+    local_cell_id: i64,
+    arena: bool,
+) -> Vec<i32> {
+    if session.get("benchEnabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return settings.snipe_list.clone();
+    }
+    let position = if arena {
+        "arena".to_string()
+    } else {
+        session
+            .get("myTeam")
+            .and_then(|v| v.as_array())
+            .and_then(|tm| {
+                tm.iter()
+                    .find(|m| m.get("cellId").and_then(|x| x.as_i64()) == Some(local_cell_id))
+            })
+            .and_then(|m| m.get("assignedPosition"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_lowercase()
+    };
+    settings.sr_picks.get(&position).cloned().unwrap_or_default()
+}
+
+/// 收到交换时的决定：Some(true) 接受，Some(false) 拒绝，None 交给玩家
+///
+/// 参数为双方英雄在预设列表中的位置（越小越优先）。
+fn trade_decision(theirs: Option<usize>, mine: Option<usize>) -> Option<bool> {
+    match (theirs, mine) {
+        (Some(t), Some(m)) => Some(t < m),
+        (Some(_), None) => Some(true),
+        (None, Some(_)) => Some(false),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lcu::FakeLcu;
+    use serde_json::json;
+
+    fn heroes() -> Arc<Mutex<HashMap<i32, Hero>>> {
+        Arc::new(Mutex::new(HashMap::new()))
+    }
+
+    fn settings() -> Settings {
+        let mut s = Settings {
+            trade_accept: true,
+            trade_decline: true,
+            ..Default::default()
+        };
+        s.sr_picks.insert("middle".into(), vec![103, 7]);
+        s
+    }
+
+    /// 自己在 2 号格子（中路）使用 `mine`，1 号格子的队友使用 `theirs`
+    fn session(mine: i32, theirs: i32, state: &str) -> serde_json::Value {
+        json!({
+            "localPlayerCellId": 2,
+            "benchEnabled": false,
+            "myTeam": [
+                { "cellId": 1, "assignedPosition": "top", "championId": theirs },
+                { "cellId": 2, "assignedPosition": "middle", "championId": mine }
+            ],
+            "trades": [{ "id": 11, "cellId": 1, "state": state }]
+        })
+    }
+
+    fn posts(api: &FakeLcu) -> Vec<String> {
+        api.requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| r.endpoint)
+            .collect()
+    }
+
+    #[test]
+    fn decision_follows_preset_priority() {
+        assert_eq!(trade_decision(Some(0), Some(1)), Some(true));
+        assert_eq!(trade_decision(Some(1), Some(0)), Some(false));
+        assert_eq!(trade_decision(Some(1), None), Some(true));
+        assert_eq!(trade_decision(None, Some(1)), Some(false));
+        assert_eq!(trade_decision(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn accepts_trade_for_higher_priority_champion_once() {
+        let api = FakeLcu::new();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut cs = ChampSelectState::default();
+
+        for _ in 0..2 {
+            handle_trades(&api, &settings(), &tx, &session(7, 103, "RECEIVED"), 2, &mut cs, &heroes()).await;
+        }
+
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/trades/11/accept"]);
+        assert!(rx.try_iter().any(|m| matches!(m, GuiMsg::Log(ref s) if s.contains("收到交换请求"))));
+    }
+
+    #[tokio::test]
+    async fn declines_trade_that_takes_away_preset_pick() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        handle_trades(&api, &settings(), &tx, &session(103, 1, "RECEIVED"), 2, &mut ChampSelectState::default(), &heroes()).await;
+
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/trades/11/decline"]);
+    }
+
+    #[tokio::test]
+    async fn leaves_unlisted_trades_to_the_player() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+
+        handle_trades(&api, &settings(), &tx, &session(1, 2, "RECEIVED"), 2, &mut ChampSelectState::default(), &heroes()).await;
+
+        assert!(posts(&api).is_empty());
+    }
+
+    #[tokio::test]
+    async fn requests_preferred_champion_from_teammate_once() {
+        let api = FakeLcu::new();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut s = settings();
+        s.trade_request = true;
+        let mut cs = ChampSelectState::default();
+
+        for _ in 0..2 {
+            handle_trades(&api, &s, &tx, &session(7, 103, "AVAILABLE"), 2, &mut cs, &heroes()).await;
+        }
+
+        assert_eq!(posts(&api), vec!["/lol-champ-select/v1/session/trades/11/request"]);
+    }
+}
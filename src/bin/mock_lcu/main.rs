@@ -0,0 +1,79 @@
+//! 模拟英雄联盟客户端 (LCU)，用于离线开发与端到端测试
+//!
+//! 用法:
+//! ```text
+//! cargo run --features mock-lcu --bin mock_lcu -- [--port 2999] [--password mock]
+//!     [--scenario ranked|aram|arena] [--cell 2] [--lockfile-dir <目录>]
+//! ```
+//! 启动后设置 `LCU_URL` / `LCU_TOKEN` 环境变量运行助手，或将 `--lockfile-dir`
+//! 填入助手的客户端目录。模拟器不提供 WebSocket 推送，助手会自动回退到轮询。
+
+mod scenario;
+mod server;
+
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+use scenario::{MockState, Scenario};
+
+struct Options {
+    port: u16,
+    password: String,
+    scenario: Scenario,
+    local_cell: i64,
+    lockfile_dir: Option<String>,
+}
+
+fn parse_args() -> anyhow::Result<Options> {
+    let mut opts = Options {
+        port: 2999,
+        password: "mock".into(),
+        scenario: Scenario::Ranked,
+        local_cell: 2,
+        lockfile_dir: None,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{} 缺少参数", arg))
+        };
+        match arg.as_str() {
+            "--port" => opts.port = value()?.parse()?,
+            "--password" => opts.password = value()?,
+            "--scenario" => {
+                let v = value()?;
+                opts.scenario =
+                    Scenario::parse(&v).ok_or_else(|| anyhow::anyhow!("未知场景: {}", v))?;
+            }
+            "--cell" => opts.local_cell = value()?.parse()?,
+            "--lockfile-dir" => opts.lockfile_dir = Some(value()?),
+            _ => anyhow::bail!("未知参数: {}", arg),
+        }
+    }
+    Ok(opts)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = parse_args()?;
+    let listener = TcpListener::bind(("127.0.0.1", opts.port)).await?;
+    let state = Arc::new(Mutex::new(MockState::new(opts.scenario, opts.local_cell)));
+
+    if let Some(dir) = &opts.lockfile_dir {
+        // 与真实客户端相同的格式，pid 使用当前进程以通过存活校验
+        let content = format!(
+            "LeagueClient:{}:{}:{}:https",
+            std::process::id(),
+            opts.port,
+            opts.password
+        );
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(std::path::Path::new(dir).join("lockfile"), content)?;
+    }
+
+    println!("[mock] 场景: {:?}，阶段: {}", opts.scenario, state.lock().unwrap().phase());
+    println!("[mock] LCU_URL=https://127.0.0.1:{} LCU_TOKEN={}", opts.port, opts.password);
+
+    server::serve(listener, state, &opts.password).await
+}
@@ -0,0 +1,1075 @@
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// 意向阶段时长
+const PLANNING_TIME: Duration = Duration::from_secs(5);
+/// 每轮 Ban/Pick 的时长
+const TURN_TIME: Duration = Duration::from_secs(30);
+/// 大乱斗选人阶段时长
+const ARAM_TIME: Duration = Duration::from_secs(40);
+/// 锁定后的准备阶段时长
+const FINALIZATION_TIME: Duration = Duration::from_secs(10);
+/// 匹配到对局所需时间
+const SEARCH_TIME: Duration = Duration::from_secs(2);
+/// 对局时长
+const GAME_TIME: Duration = Duration::from_secs(5);
+
+/// 机器人按顺序挑选的英雄
+const BOT_POOL: [i32; 16] = [
+    266, 103, 84, 12, 32, 34, 1, 22, 136, 268, 432, 53, 63, 201, 51, 122,
+];
+/// 模拟账号未拥有、不可选择的英雄
+const NOT_OWNED: [i32; 2] = [157, 777];
+/// 英雄ID上限，用于生成可选/可禁用列表
+const MAX_CHAMPION_ID: i32 = 950;
+/// 大乱斗中陆续出现在板凳席上的英雄（秒数, 英雄ID）
+const ARAM_BENCH_SCHEDULE: [(u64, i32); 4] = [(2, 222), (6, 21), (10, 157), (15, 99)];
+
+/// 可编辑的符文页数量
+const RUNE_PAGE_LIMIT: usize = 2;
+/// 排位中先手选人的队友在意向阶段发来的换顺序请求ID
+const PICK_ORDER_SWAP_ID: i64 = 1;
+/// 交换ID = 队友格子 + TRADE_ID_BASE
+const TRADE_ID_BASE: i64 = 100;
+/// 英雄选择聊天房间
+const CHAMP_SELECT_ROOM: &str = "mock@champ-select.pvp.net";
+/// 大乱斗每局可用的重随次数
+const ARAM_REROLLS: i64 = 2;
+/// 开局时的召唤师技能（闪现、点燃）
+const DEFAULT_SPELLS: [i32; 2] = [4, 14];
+
+const POSITIONS: [&str; 5] = ["top", "jungle", "middle", "bottom", "utility"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Scenario {
+    /// 单双排征召：意向 -> 全员同时禁用 -> 1-2-2-2-2-1 选人
+    Ranked,
+    /// 大乱斗：随机英雄 + 板凳席
+    Aram,
+    /// 斗魂竞技场：四支双人队伍，意向 -> 全员同时禁用 -> 两轮选人
+    Arena,
+}
+
+impl Scenario {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ranked" => Some(Scenario::Ranked),
+            "aram" => Some(Scenario::Aram),
+            "arena" => Some(Scenario::Arena),
+            _ => None,
+        }
+    }
+
+    fn queue_id(self) -> i64 {
+        match self {
+            Scenario::Ranked => 420,
+            Scenario::Aram => 450,
+            Scenario::Arena => 1700,
+        }
+    }
+
+    fn game_mode(self) -> &'static str {
+        match self {
+            Scenario::Ranked => "CLASSIC",
+            Scenario::Aram => "ARAM",
+            Scenario::Arena => "CHERRY",
+        }
+    }
+
+    fn map_id(self) -> i64 {
+        match self {
+            Scenario::Ranked => 11,
+            Scenario::Aram => 12,
+            Scenario::Arena => 30,
+        }
+    }
+}
+
+/// 模拟接口出错时返回给客户端的信息
+#[derive(Debug)]
+pub struct MockError {
+    pub status: u16,
+    pub error_code: &'static str,
+    pub message: String,
+}
+
+impl MockError {
+    fn new(status: u16, error_code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            error_code,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(404, "RPC_ERROR", message)
+    }
+}
+
+struct Action {
+    id: i64,
+    actor: i64,
+    kind: &'static str,
+    champion: i32,
+    completed: bool,
+}
+
+enum Stage {
+    Planning,
+    /// 同时进行的一组 action 下标
+    Turn(Vec<usize>),
+    Aram,
+    Finalization,
+}
+
+struct Draft {
+    local_cell: i64,
+    /// 每支队伍的人数，竞技场为 2
+    team_size: i64,
+    stages: Vec<Stage>,
+    stage: usize,
+    stage_started: Instant,
+    started: Instant,
+    actions: Vec<Action>,
+    /// 每个格子当前的英雄（大乱斗开局随机，峡谷选人后写入）
+    champions: Vec<i32>,
+    bench: Vec<i32>,
+    /// 本地玩家的召唤师技能
+    spells: [i32; 2],
+    /// 本地玩家选择的皮肤，0 表示默认
+    skin: i32,
+    /// 已与本地玩家交换过英雄的格子
+    traded: Vec<i64>,
+    /// 换选人顺序请求的状态，None 表示没有请求
+    pick_order_swap: Option<&'static str>,
+}
+
+impl Draft {
+    fn ranked(local_cell: i64, now: Instant) -> Self {
+        let picks = vec![vec![0], vec![5, 6], vec![1, 2], vec![7, 8], vec![3, 4], vec![9]];
+        Self::with_turns(local_cell, now, 10, 5, picks)
+    }
+
+    fn arena(local_cell: i64, now: Instant) -> Self {
+        Self::with_turns(local_cell, now, 8, 2, vec![vec![0, 2, 4, 6], vec![1, 3, 5, 7]])
+    }
+
+    /// 意向阶段 + 全员同时禁用 + 按组依次选人
+    fn with_turns(
+        local_cell: i64,
+        now: Instant,
+        players: i64,
+        team_size: i64,
+        pick_groups: Vec<Vec<i64>>,
+    ) -> Self {
+        let mut actions = Vec::new();
+        let mut stages = vec![Stage::Planning];
+        let mut next_id = 1;
+
+        let bans: Vec<usize> = (0..players)
+            .map(|cell| {
+                actions.push(Action {
+                    id: next_id,
+                    actor: cell,
+                    kind: "ban",
+                    champion: 0,
+                    completed: false,
+                });
+                next_id += 1;
+                actions.len() - 1
+            })
+            .collect();
+        stages.push(Stage::Turn(bans));
+
+        for group in pick_groups {
+            let idx = group
+                .into_iter()
+                .map(|cell| {
+                    actions.push(Action {
+                        id: next_id,
+                        actor: cell,
+                        kind: "pick",
+                        champion: 0,
+                        completed: false,
+                    });
+                    next_id += 1;
+                    actions.len() - 1
+                })
+                .collect();
+            stages.push(Stage::Turn(idx));
+        }
+        stages.push(Stage::Finalization);
+
+        Self {
+            local_cell,
+            team_size,
+            stages,
+            stage: 0,
+            stage_started: now,
+            started: now,
+            actions,
+            champions: vec![0; players as usize],
+            bench: Vec::new(),
+            spells: DEFAULT_SPELLS,
+            skin: 0,
+            traded: Vec::new(),
+            pick_order_swap: (team_size == 5 && local_cell % 5 != 0).then_some("RECEIVED"),
+        }
+    }
+
+    fn aram(local_cell: i64, now: Instant) -> Self {
+        Self {
+            local_cell,
+            team_size: 5,
+            stages: vec![Stage::Aram, Stage::Finalization],
+            stage: 0,
+            stage_started: now,
+            started: now,
+            actions: Vec::new(),
+            champions: BOT_POOL[..10].to_vec(),
+            bench: Vec::new(),
+            spells: DEFAULT_SPELLS,
+            skin: 0,
+            traded: Vec::new(),
+            pick_order_swap: None,
+        }
+    }
+
+    fn is_aram(&self) -> bool {
+        matches!(self.stages.first(), Some(Stage::Aram))
+    }
+
+    /// 大乱斗或准备阶段可以与队友交换英雄，每个队友只能交换一次
+    fn trades(&self) -> Vec<Value> {
+        if !matches!(self.stages[self.stage], Stage::Aram | Stage::Finalization) {
+            return Vec::new();
+        }
+        (0..self.champions.len() as i64)
+            .filter(|&c| c != self.local_cell && self.is_ally(c))
+            .map(|c| {
+                json!({
+                    "id": c + TRADE_ID_BASE,
+                    "cellId": c,
+                    "state": if self.traded.contains(&c) { "INVALID" } else { "AVAILABLE" },
+                })
+            })
+            .collect()
+    }
+
+    /// 本方第一个格子（排位中第一个选人的队友）
+    fn first_ally(&self) -> i64 {
+        self.local_cell / self.team_size * self.team_size
+    }
+
+    /// 处理换选人顺序请求：接受时交换双方的选人 action
+    fn answer_pick_order_swap(&mut self, accept: bool) -> Result<Value, MockError> {
+        let planning = matches!(self.stages[self.stage], Stage::Planning);
+        if self.pick_order_swap != Some("RECEIVED") || !planning {
+            return Err(MockError::new(500, "RPC_ERROR", "No pending swap"));
+        }
+        if accept {
+            let (me, other) = (self.local_cell, self.first_ally());
+            for a in self.actions.iter_mut().filter(|a| a.kind == "pick") {
+                if a.actor == me {
+                    a.actor = other;
+                } else if a.actor == other {
+                    a.actor = me;
+                }
+            }
+        }
+        self.pick_order_swap = Some(if accept { "ACCEPTED" } else { "DECLINED" });
+        println!("[mock] 换选人顺序: {}", if accept { "接受" } else { "拒绝" });
+        Ok(Value::Null)
+    }
+
+    fn is_ally(&self, cell: i64) -> bool {
+        cell / self.team_size == self.local_cell / self.team_size
+    }
+
+    fn unavailable(&self) -> Vec<i32> {
+        self.actions
+            .iter()
+            .filter(|a| a.completed && a.champion > 0)
+            .map(|a| a.champion)
+            .chain(self.champions.iter().copied().filter(|&c| c > 0))
+            .chain(self.bench.iter().copied())
+            .collect()
+    }
+
+    fn bot_choice(&self) -> i32 {
+        let taken = self.unavailable();
+        BOT_POOL
+            .iter()
+            .copied()
+            .find(|c| !taken.contains(c))
+            .unwrap_or(0)
+    }
+
+    fn complete(&mut self, idx: usize, champion: i32) {
+        let action = &mut self.actions[idx];
+        action.champion = champion;
+        action.completed = true;
+        if action.kind == "pick" {
+            self.champions[action.actor as usize] = champion;
+        }
+    }
+
+    /// 推进时间，返回 true 表示选人已结束
+    fn tick(&mut self, now: Instant) -> bool {
+        loop {
+            let elapsed = now.duration_since(self.stage_started);
+            let done = match &self.stages[self.stage] {
+                Stage::Planning => elapsed >= PLANNING_TIME,
+                Stage::Aram => {
+                    let since_start = now.duration_since(self.started);
+                    for (secs, champ) in ARAM_BENCH_SCHEDULE {
+                        if since_start >= Duration::from_secs(secs)
+                            && !self.bench.contains(&champ)
+                            && !self.champions.contains(&champ)
+                        {
+                            self.bench.push(champ);
+                        }
+                    }
+                    elapsed >= ARAM_TIME
+                }
+                Stage::Turn(group) => {
+                    let group = group.clone();
+                    for (n, &idx) in group.iter().enumerate() {
+                        if self.actions[idx].completed {
+                            continue;
+                        }
+                        let is_local = self.actions[idx].actor == self.local_cell;
+                        // 机器人在回合开始 2~4 秒后操作，超时则强制结束
+                        let bot_due = !is_local && elapsed >= Duration::from_secs(2 + n as u64 % 3);
+                        if bot_due || elapsed >= TURN_TIME {
+                            let champ = if is_local && self.actions[idx].champion > 0 {
+                                self.actions[idx].champion
+                            } else if self.actions[idx].kind == "ban" && is_local {
+                                0
+                            } else {
+                                self.bot_choice()
+                            };
+                            self.complete(idx, champ);
+                        }
+                    }
+                    group.iter().all(|&i| self.actions[i].completed)
+                }
+                Stage::Finalization => return elapsed >= FINALIZATION_TIME,
+            };
+            if !done {
+                return false;
+            }
+            self.stage += 1;
+            self.stage_started = now;
+        }
+    }
+
+    fn stage_time(&self) -> Duration {
+        match self.stages[self.stage] {
+            Stage::Planning => PLANNING_TIME,
+            Stage::Turn(_) => TURN_TIME,
+            Stage::Aram => ARAM_TIME,
+            Stage::Finalization => FINALIZATION_TIME,
+        }
+    }
+
+    fn in_progress(&self, idx: usize) -> bool {
+        matches!(&self.stages[self.stage], Stage::Turn(g) if g.contains(&idx))
+    }
+
+    /// 本地玩家当前英雄的皮肤：默认皮肤、一个带炫彩的皮肤和一个未拥有的皮肤
+    fn skin_carousel(&self) -> Value {
+        let champ = self.champions[self.local_cell as usize];
+        if champ == 0 {
+            return json!([]);
+        }
+        let base = champ * 1000;
+        json!([
+            { "id": base, "championId": champ, "name": "默认", "unlocked": true, "childSkins": [] },
+            { "id": base + 1, "championId": champ, "name": "模拟皮肤", "unlocked": true, "childSkins": [
+                { "id": base + 2, "championId": champ, "name": "模拟皮肤 (炫彩)", "unlocked": true }
+            ] },
+            { "id": base + 3, "championId": champ, "name": "未拥有皮肤", "unlocked": false, "childSkins": [] },
+        ])
+    }
+
+    fn session(&self, now: Instant, game_id: i64) -> Value {
+        let timer_phase = match self.stages[self.stage] {
+            Stage::Planning => "PLANNING",
+            Stage::Turn(_) | Stage::Aram => "BAN_PICK",
+            Stage::Finalization => "FINALIZATION",
+        };
+        let left = self
+            .stage_time()
+            .saturating_sub(now.duration_since(self.stage_started));
+
+        let member = |cell: i64| {
+            let spells = if cell == self.local_cell { self.spells } else { DEFAULT_SPELLS };
+            let intent = self
+                .actions
+                .iter()
+                .find(|a| a.actor == cell && a.kind == "pick" && !a.completed)
+                .map(|a| a.champion)
+                .unwrap_or(0);
+            json!({
+                "cellId": cell,
+                "assignedPosition": if self.is_aram() || self.team_size != 5 {
+                    ""
+                } else {
+                    POSITIONS[(cell % 5) as usize]
+                },
+                "championId": self.champions[cell as usize],
+                "championPickIntent": intent,
+                "spell1Id": spells[0],
+                "spell2Id": spells[1],
+                "selectedSkinId": if cell == self.local_cell && self.skin > 0 {
+                    self.skin
+                } else {
+                    self.champions[cell as usize] * 1000
+                },
+                "summonerId": 1000 + cell,
+                "puuid": format!("mock-puuid-{}", cell),
+                "team": cell / self.team_size + 1,
+            })
+        };
+
+        let cells = 0..self.champions.len() as i64;
+        let mut groups: Vec<Vec<Value>> = Vec::new();
+        for stage in &self.stages {
+            if let Stage::Turn(group) = stage {
+                groups.push(
+                    group
+                        .iter()
+                        .map(|&i| {
+                            let a = &self.actions[i];
+                            json!({
+                                "id": a.id,
+                                "actorCellId": a.actor,
+                                "championId": a.champion,
+                                "completed": a.completed,
+                                "isAllyAction": self.is_ally(a.actor),
+                                "isInProgress": self.in_progress(i),
+                                "type": a.kind,
+                            })
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        let team_bans = |ally: bool| -> Vec<i32> {
+            self.actions
+                .iter()
+                .filter(|a| a.kind == "ban" && a.completed && a.champion > 0 && self.is_ally(a.actor) == ally)
+                .map(|a| a.champion)
+                .collect()
+        };
+
+        json!({
+            "gameId": game_id,
+            "localPlayerCellId": self.local_cell,
+            "benchEnabled": self.is_aram(),
+            "benchChampions": self.bench.iter().map(|c| json!({ "championId": c, "isPriority": false })).collect::<Vec<_>>(),
+            "myTeam": cells.clone().filter(|&c| self.is_ally(c)).map(member).collect::<Vec<_>>(),
+            "theirTeam": cells.filter(|&c| !self.is_ally(c)).map(member).collect::<Vec<_>>(),
+            "actions": groups,
+            "bans": {
+                "myTeamBans": team_bans(true),
+                "theirTeamBans": team_bans(false),
+                "numBans": self.champions.len(),
+            },
+            "timer": {
+                "phase": timer_phase,
+                "adjustedTimeLeftInPhase": left.as_millis() as u64,
+                "totalTimeInPhase": self.stage_time().as_millis() as u64,
+                "isInfinite": false,
+            },
+            "trades": self.trades(),
+            "pickOrderSwaps": self.pick_order_swap.map(|state| json!([{
+                "id": PICK_ORDER_SWAP_ID,
+                "cellId": self.first_ally(),
+                "state": state,
+            }])).unwrap_or(json!([])),
+            "positionSwaps": [],
+            "isCustomGame": false,
+        })
+    }
+}
+
+/// 模拟客户端的整体状态
+pub struct MockState {
+    scenario: Scenario,
+    local_cell: i64,
+    phase: &'static str,
+    phase_since: Instant,
+    draft: Option<Draft>,
+    game_id: i64,
+    honored: bool,
+    /// 上一局自己使用的英雄（结算数据用）
+    last_champion: i32,
+    /// 符文页，第一页为不可删除的推荐页
+    rune_pages: Vec<Value>,
+    next_page_id: i64,
+    item_sets: Value,
+    rerolls: i64,
+}
+
+impl MockState {
+    pub fn new(scenario: Scenario, local_cell: i64) -> Self {
+        Self {
+            scenario,
+            local_cell,
+            phase: "Lobby",
+            phase_since: Instant::now(),
+            draft: None,
+            game_id: 1,
+            honored: false,
+            last_champion: 0,
+            rune_pages: vec![
+                json!({ "id": 1, "name": "推荐", "isDeletable": false, "current": false }),
+                json!({ "id": 2, "name": "我的符文", "isDeletable": true, "current": true }),
+            ],
+            next_page_id: 3,
+            item_sets: json!({ "accountId": 1000 + local_cell, "itemSets": [], "timestamp": 0 }),
+            rerolls: ARAM_REROLLS,
+        }
+    }
+
+    pub fn phase(&self) -> &'static str {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: &'static str, now: Instant) {
+        println!("[mock] 阶段: {} -> {}", self.phase, phase);
+        self.phase = phase;
+        self.phase_since = now;
+    }
+
+    fn enter_champ_select(&mut self, now: Instant) {
+        self.rerolls = ARAM_REROLLS;
+        self.draft = Some(match self.scenario {
+            Scenario::Ranked => Draft::ranked(self.local_cell, now),
+            Scenario::Aram => Draft::aram(self.local_cell, now),
+            Scenario::Arena => Draft::arena(self.local_cell, now),
+        });
+        self.set_phase("ChampSelect", now);
+    }
+
+    /// 根据时间推进阶段（每次请求前调用）
+    pub fn tick(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.phase_since);
+        match self.phase {
+            "Matchmaking" if elapsed >= SEARCH_TIME => self.set_phase("ReadyCheck", now),
+            "ChampSelect" if self.draft.as_mut().is_some_and(|d| d.tick(now)) => {
+                let local = self.local_cell as usize;
+                self.last_champion = self.draft.take().map_or(0, |d| d.champions[local]);
+                self.set_phase("InProgress", now);
+            }
+            "InProgress" if elapsed >= GAME_TIME => {
+                self.honored = false;
+                self.set_phase("EndOfGame", now);
+            }
+            _ => {}
+        }
+    }
+
+    /// 处理一次请求，返回 JSON 响应
+    pub fn handle(
+        &mut self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        now: Instant,
+    ) -> Result<Value, MockError> {
+        self.tick(now);
+        match (method, path) {
+            ("GET", "/lol-gameflow/v1/gameflow-phase") => Ok(json!(self.phase)),
+            ("GET", "/lol-gameflow/v1/session") => Ok(json!({
+                "phase": self.phase,
+                "gameData": { "gameId": self.game_id, "queue": { "id": self.scenario.queue_id() } },
+                "map": { "id": self.scenario.map_id(), "gameMode": self.scenario.game_mode() },
+            })),
+            ("GET", "/lol-lobby/v2/lobby/matchmaking/search-state") => Ok(json!({
+                "searchState": if self.phase == "Matchmaking" { "Searching" } else { "Invalid" },
+            })),
+            ("POST", "/lol-lobby/v2/lobby/matchmaking/search") => {
+                if self.phase != "Lobby" {
+                    return Err(MockError::new(400, "INVALID_STATE", "Not in lobby"));
+                }
+                self.set_phase("Matchmaking", now);
+                Ok(Value::Null)
+            }
+            ("GET", "/lol-matchmaking/v1/ready-check") => Ok(json!({
+                "state": if self.phase == "ReadyCheck" { "InProgress" } else { "Invalid" },
+                "playerResponse": "None",
+            })),
+            ("POST", "/lol-matchmaking/v1/ready-check/accept") => {
+                if self.phase != "ReadyCheck" {
+                    return Err(MockError::new(500, "RPC_ERROR", "No ready check in progress"));
+                }
+                self.enter_champ_select(now);
+                Ok(Value::Null)
+            }
+            ("GET", "/lol-champ-select/v1/session") => match &self.draft {
+                Some(d) => Ok(d.session(now, self.game_id)),
+                None => Err(MockError::not_found("No active delegate")),
+            },
+            ("GET", "/lol-champ-select/v1/pickable-champion-ids") => Ok(json!((1..=MAX_CHAMPION_ID)
+                .filter(|c| !NOT_OWNED.contains(c))
+                .collect::<Vec<_>>())),
+            ("GET", "/lol-champ-select/v1/bannable-champion-ids") => {
+                Ok(json!((1..=MAX_CHAMPION_ID).collect::<Vec<_>>()))
+            }
+            ("PATCH", p) if p.starts_with("/lol-champ-select/v1/session/actions/") => {
+                let id: i64 = p.rsplit('/').next().unwrap_or("").parse().unwrap_or(-1);
+                self.patch_action(id, body.unwrap_or(Value::Null))
+            }
+            ("GET", "/lol-champ-select/v1/skin-carousel-skins") => match &self.draft {
+                Some(d) => Ok(d.skin_carousel()),
+                None => Err(MockError::not_found("No active delegate")),
+            },
+            ("PATCH", "/lol-champ-select/v1/session/my-selection") => {
+                self.patch_selection(body.unwrap_or(Value::Null))
+            }
+            ("GET", "/lol-summoner/v1/current-summoner/rerollPoints") => Ok(json!({
+                "currentPoints": self.rerolls * 250,
+                "maxRolls": ARAM_REROLLS,
+                "numberOfRolls": self.rerolls,
+                "pointsCostToRoll": 250,
+                "pointsToReroll": 0,
+            })),
+            ("POST", "/lol-champ-select/v1/session/my-selection/reroll") => self.reroll(),
+            ("POST", p) if p.starts_with("/lol-champ-select/v1/session/pick-order-swaps/") => {
+                let draft = self
+                    .draft
+                    .as_mut()
+                    .ok_or_else(|| MockError::not_found("No active delegate"))?;
+                match p.rsplit('/').next().unwrap_or("") {
+                    "accept" => draft.answer_pick_order_swap(true),
+                    "decline" => draft.answer_pick_order_swap(false),
+                    _ => Err(MockError::not_found("Unknown swap action")),
+                }
+            }
+            ("POST", p) if p.starts_with("/lol-champ-select/v1/session/trades/") => {
+                let mut parts = p.rsplit('/');
+                let action = parts.next().unwrap_or("");
+                let id: i64 = parts.next().unwrap_or("").parse().unwrap_or(-1);
+                match action {
+                    "request" => self.trade(id),
+                    _ => Err(MockError::new(500, "RPC_ERROR", "No incoming trade")),
+                }
+            }
+            ("POST", p) if p.starts_with("/lol-champ-select/v1/session/bench/swap/") => {
+                let champ: i32 = p.rsplit('/').next().unwrap_or("").parse().unwrap_or(-1);
+                self.bench_swap(champ)
+            }
+            ("GET", "/lol-end-of-game/v1/eog-stats-block") => {
+                if self.phase != "EndOfGame" {
+                    return Err(MockError::not_found("No end of game stats"));
+                }
+                let player = |cell: i64| json!({
+                    "puuid": format!("mock-puuid-{}", cell),
+                    "riotIdGameName": format!("Bot{}", cell),
+                    "riotIdTagLine": "0000",
+                    "championId": if cell == self.local_cell { self.last_champion } else { 0 },
+                    "stats": {
+                        "CHAMPIONS_KILLED": 2 + cell,
+                        "NUM_DEATHS": 5 - cell,
+                        "ASSISTS": 6,
+                        "TOTAL_DAMAGE_DEALT_TO_CHAMPIONS": 12000 + 3000 * (cell % 3),
+                    },
+                });
+                Ok(json!({
+                    "gameId": self.game_id,
+                    "gameLength": GAME_TIME.as_secs() * 300,
+                    "queueId": self.scenario.queue_id(),
+                    "localPlayer": {
+                        "puuid": format!("mock-puuid-{}", self.local_cell),
+                        "championId": self.last_champion,
+                        "stats": { "CHAMPIONS_KILLED": 8, "NUM_DEATHS": 3, "ASSISTS": 11 },
+                    },
+                    "teams": [{
+                        "isPlayerTeam": true,
+                        "isWinningTeam": self.game_id % 2 == 1,
+                        "players": (0..5).map(player).collect::<Vec<_>>(),
+                    }],
+                }))
+            }
+            ("GET", "/lol-ranked/v1/current-lp-change-notification") => Ok(json!({
+                "gameId": self.game_id,
+                "leaguePointsDelta": if self.game_id % 2 == 1 { 21 } else { -18 },
+            })),
+            ("GET", "/lol-chat/v1/friends") => Ok(json!([
+                { "puuid": "mock-puuid-1", "gameName": "Bot1", "gameTag": "0000" },
+            ])),
+            ("GET", "/lol-honor-v2/v1/ballot") => {
+                if self.phase == "EndOfGame" && !self.honored {
+                    Ok(json!({
+                        "gameId": self.game_id,
+                        "eligibleAllies": (0..5).filter(|&c| c != self.local_cell).map(|c| json!({
+                            "summonerId": 1000 + c,
+                            "puuid": format!("mock-puuid-{}", c),
+                            "summonerName": format!("Bot{}", c),
+                            "gameName": format!("Bot{}", c),
+                            "tagLine": "0000",
+                        })).collect::<Vec<_>>(),
+                        "eligibleOpponents": [],
+                    }))
+                } else {
+                    Err(MockError::not_found("No ballot"))
+                }
+            }
+            ("POST", "/lol-honor-v2/v1/honor-player") => {
+                self.honored = true;
+                println!("[mock] 点赞: {}", body.unwrap_or(Value::Null));
+                Ok(Value::Null)
+            }
+            ("POST", "/lol-lobby/v2/play-again") => {
+                if self.phase != "EndOfGame" {
+                    return Err(MockError::new(400, "INVALID_STATE", "Game has not ended"));
+                }
+                self.game_id += 1;
+                self.set_phase("Lobby", now);
+                Ok(Value::Null)
+            }
+            ("GET", "/lol-summoner/v1/current-summoner") => Ok(json!({
+                "summonerId": 1000 + self.local_cell,
+                "puuid": format!("mock-puuid-{}", self.local_cell),
+                "gameName": "Mock",
+                "tagLine": "0000",
+            })),
+            ("GET", p) if p == self.item_sets_path() => Ok(self.item_sets.clone()),
+            ("PUT", p) if p == self.item_sets_path() => {
+                self.item_sets = body.unwrap_or(Value::Null);
+                println!(
+                    "[mock] 装备方案: {} 套",
+                    self.item_sets["itemSets"].as_array().map_or(0, |a| a.len())
+                );
+                Ok(self.item_sets.clone())
+            }
+            ("GET", "/lol-perks/v1/pages") => Ok(json!(self.rune_pages)),
+            ("GET", "/lol-perks/v1/inventory") => Ok(json!({ "ownedPageCount": RUNE_PAGE_LIMIT })),
+            ("GET", "/lol-perks/v1/currentpage") => self
+                .rune_pages
+                .iter()
+                .find(|p| p["current"] == json!(true))
+                .cloned()
+                .ok_or_else(|| MockError::not_found("No current page")),
+            ("POST", "/lol-perks/v1/pages") => self.create_rune_page(body.unwrap_or(Value::Null)),
+            ("DELETE", p) if p.starts_with("/lol-perks/v1/pages/") => {
+                let id: i64 = p.rsplit('/').next().unwrap_or("").parse().unwrap_or(-1);
+                let before = self.rune_pages.len();
+                self.rune_pages.retain(|p| p["id"] != json!(id) || p["isDeletable"] == json!(false));
+                if self.rune_pages.len() == before {
+                    return Err(MockError::not_found("Page not found"));
+                }
+                Ok(Value::Null)
+            }
+            ("PUT", p) if p.starts_with("/lol-perks/v1/pages/") => {
+                let id: i64 = p.rsplit('/').next().unwrap_or("").parse().unwrap_or(-1);
+                let page = self
+                    .rune_pages
+                    .iter_mut()
+                    .find(|p| p["id"] == json!(id) && p["isDeletable"] == json!(true))
+                    .ok_or_else(|| MockError::not_found("Page not found"))?;
+                let mut new_page = body.unwrap_or(Value::Null);
+                new_page["id"] = json!(id);
+                new_page["isDeletable"] = json!(true);
+                new_page["current"] = page["current"].clone();
+                println!("[mock] 覆盖符文页 {}: {}", id, new_page["name"]);
+                *page = new_page;
+                Ok(Value::Null)
+            }
+            ("PUT", "/lol-perks/v1/currentpage") => {
+                let id = body.and_then(|b| b.as_i64()).unwrap_or(-1);
+                if !self.rune_pages.iter().any(|p| p["id"] == json!(id)) {
+                    return Err(MockError::not_found("Page not found"));
+                }
+                for page in &mut self.rune_pages {
+                    page["current"] = json!(page["id"] == json!(id));
+                }
+                Ok(Value::Null)
+            }
+            ("PUT", "/lol-chat/v1/me") => Ok(body.unwrap_or(Value::Null)),
+            ("GET", "/lol-chat/v1/conversations") => Ok(match self.draft {
+                Some(_) => json!([{ "id": CHAMP_SELECT_ROOM, "type": "championSelect" }]),
+                None => json!([]),
+            }),
+            ("POST", p) if p == format!("/lol-chat/v1/conversations/{}/messages", CHAMP_SELECT_ROOM) => {
+                if self.draft.is_none() {
+                    return Err(MockError::not_found("Conversation not found"));
+                }
+                println!("[mock] 聊天: {}", body.as_ref().map_or(Value::Null, |b| b["body"].clone()));
+                Ok(body.unwrap_or(Value::Null))
+            }
+            ("GET", p) if p.starts_with("/lol-summoner/v1/summoners/") => {
+                let id: i64 = p.rsplit('/').next().unwrap_or("").parse().unwrap_or(-1);
+                Ok(json!({
+                    "summonerId": id,
+                    "puuid": format!("mock-puuid-{}", id - 1000),
+                    "gameName": format!("Bot{}", id - 1000),
+                    "tagLine": "0000",
+                }))
+            }
+            ("GET", p) if p.starts_with("/lol-ranked/v1/ranked-stats/") => Ok(json!({
+                "queueMap": { "RANKED_SOLO_5x5": {
+                    "tier": "SILVER", "division": "I", "wins": 42, "losses": 38,
+                } },
+            })),
+            ("GET", p) if p.starts_with("/lol-match-history/v1/products/lol/") => {
+                let games: Vec<Value> = (0..10)
+                    .map(|i| {
+                        let champion = [103, 64, 103, 7][i as usize % 4];
+                        json!({
+                            "gameId": self.game_id - 1 - i,
+                            "participants": [{
+                                "participantId": 1,
+                                "championId": champion,
+                                "stats": { "win": i % 3 != 2 },
+                            }],
+                        })
+                    })
+                    .collect();
+                Ok(json!({ "games": { "games": games } }))
+            }
+            ("GET", "/lol-ranked/v1/current-ranked-stats") => Ok(json!({
+                "queueMap": { "RANKED_SOLO_5x5": { "tier": "GOLD", "division": "II" } },
+            })),
+            _ => Err(MockError::not_found(format!("Unknown endpoint {} {}", method, path))),
+        }
+    }
+
+    fn patch_action(&mut self, id: i64, body: Value) -> Result<Value, MockError> {
+        let draft = self
+            .draft
+            .as_mut()
+            .ok_or_else(|| MockError::not_found("No active delegate"))?;
+        let idx = draft
+            .actions
+            .iter()
+            .position(|a| a.id == id && a.actor == draft.local_cell)
+            .ok_or_else(|| MockError::new(500, "RPC_ERROR", "Invalid action"))?;
+        if draft.actions[idx].completed {
+            return Err(MockError::new(500, "RPC_ERROR", "Action already completed"));
+        }
+
+        let champ = body.get("championId").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+        if champ > 0 && draft.unavailable().contains(&champ) {
+            return Err(MockError::new(500, "RPC_ERROR", "Champion is not available"));
+        }
+        if draft.actions[idx].kind == "pick" && NOT_OWNED.contains(&champ) {
+            return Err(MockError::new(500, "RPC_ERROR", "Champion is not owned"));
+        }
+        draft.actions[idx].champion = champ;
+
+        if body.get("completed").and_then(|v| v.as_bool()).unwrap_or(false) {
+            if !draft.in_progress(idx) {
+                return Err(MockError::new(500, "RPC_ERROR", "Action is not in progress"));
+            }
+            draft.complete(idx, champ);
+            println!("[mock] 锁定 {} {}", draft.actions[idx].kind, champ);
+        }
+        Ok(Value::Null)
+    }
+
+    fn patch_selection(&mut self, body: Value) -> Result<Value, MockError> {
+        let draft = self
+            .draft
+            .as_mut()
+            .ok_or_else(|| MockError::not_found("No active delegate"))?;
+        for (i, key) in ["spell1Id", "spell2Id"].into_iter().enumerate() {
+            if let Some(id) = body.get(key).and_then(|v| v.as_i64()) {
+                draft.spells[i] = id as i32;
+                println!("[mock] 召唤师技能: {:?}", draft.spells);
+            }
+        }
+        if let Some(id) = body.get("selectedSkinId").and_then(|v| v.as_i64()) {
+            draft.skin = id as i32;
+            println!("[mock] 皮肤: {}", id);
+        }
+        Ok(Value::Null)
+    }
+
+    fn item_sets_path(&self) -> String {
+        format!("/lol-item-sets/v1/item-sets/{}/sets", 1000 + self.local_cell)
+    }
+
+    fn create_rune_page(&mut self, mut page: Value) -> Result<Value, MockError> {
+        let editable = self
+            .rune_pages
+            .iter()
+            .filter(|p| p["isDeletable"] == json!(true))
+            .count();
+        if editable >= RUNE_PAGE_LIMIT {
+            return Err(MockError::new(400, "RPC_ERROR", "Max pages reached"));
+        }
+        page["id"] = json!(self.next_page_id);
+        page["isDeletable"] = json!(true);
+        self.next_page_id += 1;
+        if page["current"] == json!(true) {
+            for p in &mut self.rune_pages {
+                p["current"] = json!(false);
+            }
+        }
+        println!("[mock] 新符文页: {}", page["name"]);
+        self.rune_pages.push(page.clone());
+        Ok(page)
+    }
+
+    /// 发起交换：机器人队友总是立即接受
+    fn trade(&mut self, id: i64) -> Result<Value, MockError> {
+        let draft = self
+            .draft
+            .as_mut()
+            .ok_or_else(|| MockError::not_found("No active delegate"))?;
+        let cell = id - TRADE_ID_BASE;
+        let available = draft
+            .trades()
+            .iter()
+            .any(|t| t["id"] == json!(id) && t["state"] == json!("AVAILABLE"));
+        if !available {
+            return Err(MockError::new(500, "RPC_ERROR", "Trade is not available"));
+        }
+        draft.champions.swap(cell as usize, draft.local_cell as usize);
+        draft.traded.push(cell);
+        draft.skin = 0;
+        println!("[mock] 交换: 与 {} 号位交换英雄", cell);
+        Ok(Value::Null)
+    }
+
+    /// 重随：原英雄放到板凳席，换一个随机英雄
+    fn reroll(&mut self) -> Result<Value, MockError> {
+        if self.rerolls <= 0 {
+            return Err(MockError::new(400, "RPC_ERROR", "Not enough reroll points"));
+        }
+        let draft = self
+            .draft
+            .as_mut()
+            .filter(|d| d.is_aram())
+            .ok_or_else(|| MockError::not_found("No bench"))?;
+        let champ = draft.bot_choice();
+        let cell = draft.local_cell as usize;
+        draft.bench.push(draft.champions[cell]);
+        draft.champions[cell] = champ;
+        draft.skin = 0;
+        self.rerolls -= 1;
+        println!("[mock] 重随: {} (剩余 {})", champ, self.rerolls);
+        Ok(Value::Null)
+    }
+
+    fn bench_swap(&mut self, champ: i32) -> Result<Value, MockError> {
+        let draft = self
+            .draft
+            .as_mut()
+            .filter(|d| d.is_aram())
+            .ok_or_else(|| MockError::not_found("No bench"))?;
+        let pos = draft
+            .bench
+            .iter()
+            .position(|&c| c == champ)
+            .ok_or_else(|| MockError::new(500, "RPC_ERROR", "Champion is not on the bench"))?;
+        let cell = draft.local_cell as usize;
+        draft.bench[pos] = draft.champions[cell];
+        draft.champions[cell] = champ;
+        draft.skin = 0;
+        println!("[mock] 板凳席交换: {}", champ);
+        Ok(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "/lol-champ-select/v1/session";
+
+    /// 像助手一样完成自己正在进行的 action
+    fn act(state: &mut MockState, now: Instant, ban: i32, pick: i32) {
+        let Ok(session) = state.handle("GET", SESSION, None, now) else {
+            return;
+        };
+        let local = session["localPlayerCellId"].as_i64();
+        let actions = session["actions"].as_array().cloned().unwrap_or_default();
+        for a in actions.iter().flat_map(|g| g.as_array().cloned().unwrap_or_default()) {
+            if a["actorCellId"].as_i64() != local || a["completed"] == true || a["isInProgress"] != true {
+                continue;
+            }
+            let champ = if a["type"] == "ban" { ban } else { pick };
+            let path = format!("{}/actions/{}", SESSION, a["id"]);
+            state
+                .handle("PATCH", &path, Some(json!({ "championId": champ, "completed": true })), now)
+                .unwrap();
+        }
+    }
+
+    /// 从房间开始匹配并接受对局，返回进入英雄选择的时间
+    fn enter_champ_select(state: &mut MockState) -> Instant {
+        let mut now = Instant::now();
+        state.handle("POST", "/lol-lobby/v2/lobby/matchmaking/search", None, now).unwrap();
+        assert_eq!(state.phase(), "Matchmaking");
+        now += SEARCH_TIME;
+        state.tick(now);
+        assert_eq!(state.phase(), "ReadyCheck");
+        state.handle("POST", "/lol-matchmaking/v1/ready-check/accept", None, now).unwrap();
+        assert_eq!(state.phase(), "ChampSelect");
+        now
+    }
+
+    #[test]
+    fn ranked_scenario_steps_from_lobby_to_end_of_game() {
+        let mut state = MockState::new(Scenario::Ranked, 2);
+        assert_eq!(state.phase(), "Lobby");
+        let mut now = enter_champ_select(&mut state);
+
+        let session = state.handle("GET", SESSION, None, now).unwrap();
+        assert_eq!(session["timer"]["phase"], "PLANNING");
+        assert_eq!(session["myTeam"][2]["assignedPosition"], "middle");
+
+        let mut saw_ban = false;
+        for _ in 0..300 {
+            if state.phase() != "ChampSelect" {
+                break;
+            }
+            act(&mut state, now, 238, 64);
+            if let Ok(s) = state.handle("GET", SESSION, None, now) {
+                saw_ban |= s["bans"]["myTeamBans"].as_array().is_some_and(|b| b.contains(&json!(238)));
+            }
+            now += Duration::from_secs(1);
+            state.tick(now);
+        }
+        assert!(saw_ban);
+        assert_eq!(state.phase(), "InProgress");
+        assert!(state.handle("GET", "/lol-end-of-game/v1/eog-stats-block", None, now).is_err());
+
+        now += GAME_TIME;
+        state.tick(now);
+        assert_eq!(state.phase(), "EndOfGame");
+        let eog = state.handle("GET", "/lol-end-of-game/v1/eog-stats-block", None, now).unwrap();
+        assert_eq!(eog["localPlayer"]["championId"], 64);
+        assert_eq!(eog["queueId"], 420);
+    }
+
+    #[test]
+    fn aram_bench_fills_over_time_and_swaps() {
+        let mut state = MockState::new(Scenario::Aram, 0);
+        let start = enter_champ_select(&mut state);
+
+        let session = state.handle("GET", SESSION, None, start).unwrap();
+        assert_eq!(session["benchEnabled"], true);
+        assert_eq!(session["benchChampions"], json!([]));
+
+        let now = start + Duration::from_secs(3);
+        let session = state.handle("GET", SESSION, None, now).unwrap();
+        assert_eq!(session["benchChampions"][0]["championId"], 222);
+        state.handle("POST", "/lol-champ-select/v1/session/bench/swap/222", None, now).unwrap();
+        let session = state.handle("GET", SESSION, None, now).unwrap();
+        assert_eq!(session["myTeam"][0]["championId"], 222);
+        assert_eq!(session["benchChampions"][0]["championId"], BOT_POOL[0]);
+
+        state.tick(start + ARAM_TIME);
+        assert_eq!(state.phase(), "ChampSelect");
+        state.tick(start + ARAM_TIME + FINALIZATION_TIME);
+        assert_eq!(state.phase(), "InProgress");
+    }
+}
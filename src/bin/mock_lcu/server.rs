@@ -0,0 +1,125 @@
+//! 模拟客户端的 HTTPS 服务，助手的端到端测试也通过它启动模拟客户端
+
+use base64::prelude::*;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+use super::scenario::MockState;
+
+/// 生成自签名证书，与真实客户端一样需要跳过证书校验
+fn tls_acceptor() -> anyhow::Result<TlsAcceptor> {
+    let certified =
+        rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string(), "localhost".to_string()])?;
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(vec![certified.cert.der().clone()], key)?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn json_response(status: StatusCode, body: serde_json::Value) -> Response<Full<Bytes>> {
+    let mut resp = Response::new(Full::new(Bytes::from(body.to_string())));
+    *resp.status_mut() = status;
+    resp.headers_mut()
+        .insert("Content-Type", "application/json".parse().unwrap());
+    resp
+}
+
+fn error_response(status: u16, error_code: &str, message: &str) -> Response<Full<Bytes>> {
+    json_response(
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        serde_json::json!({
+            "errorCode": error_code,
+            "httpStatus": status,
+            "implementationDetails": {},
+            "message": message,
+        }),
+    )
+}
+
+async fn handle(
+    req: Request<Incoming>,
+    state: Arc<Mutex<MockState>>,
+    auth_header: Arc<String>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let authorized = req
+        .headers()
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v == auth_header.as_str());
+    if !authorized {
+        return Ok(error_response(401, "UNAUTHORIZED", "Invalid credentials"));
+    }
+
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let bytes = match req.into_body().collect().await {
+        Ok(b) => b.to_bytes(),
+        Err(_) => return Ok(error_response(400, "BAD_REQUEST", "Unreadable body")),
+    };
+    let body = if bytes.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice(&bytes) {
+            Ok(v) => Some(v),
+            Err(_) => return Ok(error_response(400, "BAD_REQUEST", "Invalid JSON")),
+        }
+    };
+
+    let result = state
+        .lock()
+        .unwrap()
+        .handle(&method, &path, body, Instant::now());
+    Ok(match result {
+        Ok(serde_json::Value::Null) => {
+            let mut resp = Response::new(Full::new(Bytes::new()));
+            *resp.status_mut() = StatusCode::NO_CONTENT;
+            resp
+        }
+        Ok(v) => json_response(StatusCode::OK, v),
+        Err(e) => {
+            println!("[mock] {} {} -> {} {}", method, path, e.status, e.message);
+            error_response(e.status, e.error_code, &e.message)
+        }
+    })
+}
+
+/// 在已绑定的端口上提供模拟客户端接口，使用与真实客户端相同的 Basic 认证
+pub async fn serve(
+    listener: TcpListener,
+    state: Arc<Mutex<MockState>>,
+    password: &str,
+) -> anyhow::Result<()> {
+    let acceptor = tls_acceptor()?;
+    let auth_header = Arc::new(format!(
+        "Basic {}",
+        BASE64_STANDARD.encode(format!("riot:{}", password))
+    ));
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let acceptor = acceptor.clone();
+        let state = state.clone();
+        let auth_header = auth_header.clone();
+        tokio::spawn(async move {
+            let Ok(tls) = acceptor.accept(stream).await else {
+                return;
+            };
+            let service = service_fn(move |req| handle(req, state.clone(), auth_header.clone()));
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(tls), service)
+                .await;
+        });
+    }
+}
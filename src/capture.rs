@@ -0,0 +1,94 @@
+use chrono::Local;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::lcu::{LcuError, LcuResult};
+use crate::models::Settings;
+use crate::utils::CAPTURE_DIR;
+
+/// LCU 请求录制器
+///
+/// 每行一条 JSON 记录，`kind` 取值：
+/// - `settings`：录制开始时的设置，回放时沿用
+/// - `request`：一次请求及其响应（`status` 为 0 表示网络错误）
+/// - `event`：WebSocket 推送的事件
+pub struct Recorder {
+    file: Mutex<fs::File>,
+    pub path: PathBuf,
+}
+
+impl Recorder {
+    /// 在录制目录下创建带时间戳的文件
+    pub fn create(settings: &Settings) -> std::io::Result<Self> {
+        fs::create_dir_all(CAPTURE_DIR)?;
+        let path = PathBuf::from(CAPTURE_DIR).join(format!(
+            "lcu-{}.jsonl",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        Self::create_at(path, settings)
+    }
+
+    /// 在指定路径创建录制文件，首行写入设置
+    pub fn create_at(path: PathBuf, settings: &Settings) -> std::io::Result<Self> {
+        let rec = Self {
+            file: Mutex::new(fs::File::create(&path)?),
+            path,
+        };
+        rec.write(serde_json::json!({
+            "kind": "settings",
+            "time": now(),
+            "settings": settings,
+        }));
+        Ok(rec)
+    }
+
+    pub fn record_request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<&serde_json::Value>,
+        result: &LcuResult<serde_json::Value>,
+    ) {
+        let (status, response) = match result {
+            Ok(v) => (200, v.clone()),
+            Err(LcuError::Http {
+                status,
+                error_code,
+                message,
+            }) => (
+                *status,
+                serde_json::json!({ "errorCode": error_code, "message": message }),
+            ),
+            Err(e) => (0, serde_json::json!({ "message": e.to_string() })),
+        };
+        self.write(serde_json::json!({
+            "kind": "request",
+            "time": now(),
+            "method": method,
+            "endpoint": endpoint,
+            "body": body,
+            "status": status,
+            "response": response,
+        }));
+    }
+
+    pub fn record_event(&self, uri: &str, data: Option<&serde_json::Value>) {
+        self.write(serde_json::json!({
+            "kind": "event",
+            "time": now(),
+            "uri": uri,
+            "data": data,
+        }));
+    }
+
+    fn write(&self, entry: serde_json::Value) {
+        let mut file = self.file.lock().unwrap();
+        let _ = writeln!(file, "{}", entry);
+    }
+}
+
+fn now() -> String {
+    Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+}
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::lcu::{LcuApi, LcuError, LcuResult};
+
+/// 记录下来的一次请求
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub endpoint: String,
+    pub body: Option<serde_json::Value>,
+}
+
+/// 预设的响应
+#[derive(Clone)]
+enum Scripted {
+    Ok(serde_json::Value),
+    Err {
+        status: u16,
+        error_code: String,
+        message: String,
+    },
+}
+
+impl Scripted {
+    fn to_result(&self) -> LcuResult<serde_json::Value> {
+        match self {
+            Scripted::Ok(v) => Ok(v.clone()),
+            Scripted::Err {
+                status,
+                error_code,
+                message,
+            } => Err(LcuError::Http {
+                status: *status,
+                error_code: error_code.clone(),
+                message: message.clone(),
+            }),
+        }
+    }
+}
+
+/// 内存中的假 LCU，用于测试
+///
+/// 按 `方法 + 路径` 返回预设的响应序列（最后一条会一直重复），
+/// 并记录收到的所有请求。未预设的路径返回空对象，与真实客户端的空响应一致。
+/// 克隆后共享同一份预设与请求记录。
+#[derive(Clone, Default)]
+pub struct FakeLcu {
+    responses: Arc<Mutex<HashMap<String, VecDeque<Scripted>>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl FakeLcu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 为某个接口追加响应
+    pub fn script(
+        &self,
+        method: &str,
+        endpoint: &str,
+        responses: impl IntoIterator<Item = serde_json::Value>,
+    ) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(format!("{} {}", method, endpoint))
+            .or_default()
+            .extend(responses.into_iter().map(Scripted::Ok));
+    }
+
+    /// 为某个接口追加一次错误响应
+    pub fn script_error(
+        &self,
+        method: &str,
+        endpoint: &str,
+        status: u16,
+        error_code: &str,
+        message: &str,
+    ) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(format!("{} {}", method, endpoint))
+            .or_default()
+            .push_back(Scripted::Err {
+                status,
+                error_code: error_code.to_string(),
+                message: message.to_string(),
+            });
+    }
+
+    /// 目前为止收到的全部请求
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// 取出并清空已记录的请求
+    pub fn take_requests(&self) -> Vec<RecordedRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+impl LcuApi for FakeLcu {
+    async fn request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> LcuResult<serde_json::Value> {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            body,
+        });
+        let mut responses = self.responses.lock().unwrap();
+        match responses.get_mut(&format!("{} {}", method, endpoint)) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().unwrap().to_result(),
+            Some(queue) => queue
+                .front()
+                .map(Scripted::to_result)
+                .unwrap_or(Ok(serde_json::json!({}))),
+            None => Ok(serde_json::json!({})),
+        }
+    }
+}
@@ -1,27 +1,116 @@
-use anyhow::Result;
 use reqwest::Client;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use sysinfo::System;
+use sysinfo::{Pid, System};
 
+use crate::capture::Recorder;
 use crate::models::{Hero, LcuConnection, Settings};
 use crate::utils::{CHAMP_FILE, IMG_DIR, VERSION_FILE};
 
+/// LCU 请求错误
+#[derive(Debug)]
+pub enum LcuError {
+    /// 网络层错误（客户端未响应、连接断开等）
+    Transport(reqwest::Error),
+    /// 客户端返回了非 2xx 状态码
+    Http {
+        status: u16,
+        /// LCU 返回体中的 `errorCode`，例如 `RPC_ERROR`
+        error_code: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for LcuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LcuError::Transport(e) => write!(f, "请求失败: {}", e),
+            LcuError::Http {
+                status,
+                error_code,
+                message,
+            } => {
+                write!(f, "HTTP {}", status)?;
+                if !error_code.is_empty() {
+                    write!(f, " [{}]", error_code)?;
+                }
+                if !message.is_empty() {
+                    write!(f, " {}", message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LcuError {}
+
+impl From<reqwest::Error> for LcuError {
+    fn from(e: reqwest::Error) -> Self {
+        LcuError::Transport(e)
+    }
+}
+
+pub type LcuResult<T> = Result<T, LcuError>;
+
+/// LCU 请求接口
+///
+/// 英雄选择与流程处理逻辑只依赖此接口，便于在测试中替换为假实现。
+pub trait LcuApi: Sync {
+    fn request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> impl Future<Output = LcuResult<serde_json::Value>> + Send;
+}
+
+/// 基于 reqwest 的默认实现
+#[derive(Clone)]
+pub struct HttpLcu {
+    pub client: Client,
+    pub conn: LcuConnection,
+    /// 录制模式下记录每次请求与响应
+    pub recorder: Option<Arc<Recorder>>,
+}
+
+impl LcuApi for HttpLcu {
+    async fn request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> LcuResult<serde_json::Value> {
+        match &self.recorder {
+            Some(rec) => {
+                let result =
+                    lcu_request(&self.client, &self.conn, method, endpoint, body.clone()).await;
+                rec.record_request(method, endpoint, body.as_ref(), &result);
+                result
+            }
+            None => lcu_request(&self.client, &self.conn, method, endpoint, body).await,
+        }
+    }
+}
+
 pub async fn lcu_request(
     client: &Client,
     conn: &LcuConnection,
     method: &str,
     endpoint: &str,
     body: Option<serde_json::Value>,
-) -> Result<serde_json::Value> {
+) -> LcuResult<serde_json::Value> {
     let url = format!("{}{}", conn.url, endpoint);
     let builder = match method {
         "GET" => client.get(&url),
         "POST" => client.post(&url),
         "PUT" => client.put(&url),
         "PATCH" => client.patch(&url),
+        "DELETE" => client.delete(&url),
         _ => client.get(&url),
     };
     let req = builder
@@ -32,9 +121,85 @@ pub async fn lcu_request(
     } else {
         req.send().await?
     };
-    Ok(resp.json().await.unwrap_or(serde_json::json!({})))
+    let status = resp.status();
+    let text = resp.text().await?;
+    let value = serde_json::from_str(&text).unwrap_or(serde_json::json!({}));
+    if !status.is_success() {
+        // 错误体格式: { "errorCode": "...", "httpStatus": 404, "message": "..." }
+        return Err(LcuError::Http {
+            status: status.as_u16(),
+            error_code: value
+                .get("errorCode")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            message: value
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        });
+    }
+    Ok(value)
 }
 
+/// 通过环境变量 `LCU_URL` / `LCU_TOKEN` 指定连接地址（用于连接模拟客户端）
+pub fn lcu_override() -> Option<(String, String)> {
+    let url = std::env::var("LCU_URL").ok()?;
+    let token = std::env::var("LCU_TOKEN").ok()?;
+    Some((url.trim_end_matches('/').to_string(), token))
+}
+
+/// 客户端默认安装目录
+const DEFAULT_CLIENT_DIRS: [&str; 2] = [
+    "C:\\Riot Games\\League of Legends",
+    "D:\\Riot Games\\League of Legends",
+];
+
+/// 通过客户端目录下的 lockfile 获取端口和令牌（无需管理员权限）
+///
+/// 优先使用配置的安装目录，否则根据正在运行的客户端进程路径推断，最后尝试默认目录。
+pub fn find_lcu_lockfile(sys: &System, client_path: &str) -> Option<(String, String)> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if !client_path.trim().is_empty() {
+        dirs.push(PathBuf::from(client_path.trim()));
+    }
+    for process in sys.processes().values() {
+        let name = process.name();
+        if name.eq_ignore_ascii_case("LeagueClientUx.exe")
+            || name.eq_ignore_ascii_case("LeagueClient.exe")
+        {
+            if let Some(dir) = process.exe().and_then(|p| p.parent()) {
+                dirs.push(dir.to_path_buf());
+            }
+        }
+    }
+    dirs.extend(DEFAULT_CLIENT_DIRS.iter().map(PathBuf::from));
+
+    dirs.iter().find_map(|dir| read_lockfile(sys, &dir.join("lockfile")))
+}
+
+/// 读取 lockfile 中的端口和令牌
+///
+/// 客户端异常退出时 lockfile 可能残留，因此会校验其中的进程是否仍在运行。
+fn read_lockfile(sys: &System, path: &Path) -> Option<(String, String)> {
+    let content = fs::read_to_string(path).ok()?;
+    let (pid, port, token) = parse_lockfile(&content)?;
+    sys.process(Pid::from_u32(pid))?;
+    Some((port, token))
+}
+
+/// 解析 lockfile 内容，格式为 `name:pid:port:password:protocol`，返回 (pid, 端口, 令牌)
+fn parse_lockfile(content: &str) -> Option<(u32, String, String)> {
+    let parts: Vec<&str> = content.trim().split(':').collect();
+    if parts.len() < 5 {
+        return None;
+    }
+    let pid = parts[1].parse::<u32>().ok()?;
+    Some((pid, parts[2].to_string(), parts[3].to_string()))
+}
+
+/// 从进程命令行读取端口和令牌（读取其他进程命令行可能需要管理员权限）
 pub fn find_lcu_process(sys: &System) -> Option<(String, String)> {
     for (_pid, process) in sys.processes() {
         if process.name().eq_ignore_ascii_case("LeagueClientUx.exe") {
@@ -85,6 +250,11 @@ pub async fn update_data(client: &Client, shared_heroes: &Arc<Mutex<HashMap<i32,
             }
         }
     }
+    load_heroes(shared_heroes);
+}
+
+/// 从本地英雄数据文件加载英雄表
+pub fn load_heroes(shared_heroes: &Arc<Mutex<HashMap<i32, Hero>>>) {
     if let Ok(content) = fs::read_to_string(CHAMP_FILE) {
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(&content) {
             if let Some(data) = v.get("data").and_then(|d| d.as_object()) {
@@ -134,7 +304,29 @@ pub async fn update_data(client: &Client, shared_heroes: &Arc<Mutex<HashMap<i32,
     }
 }
 
-pub async fn spoof_rank(client: &Client, conn: &LcuConnection, s: &Settings) {
+pub async fn spoof_rank(api: &impl LcuApi, s: &Settings) -> LcuResult<()> {
     let payload = serde_json::json!({ "lol": { "rankedLeagueTier": s.spoof_tier, "rankedLeagueDivision": s.spoof_div, "rankedLeagueQueue": "RANKED_SOLO_5x5", "rankedLeagueJo": "RUBY" } });
-    let _ = lcu_request(client, conn, "PUT", "/lol-chat/v1/me", Some(payload)).await;
+    api.request("PUT", "/lol-chat/v1/me", Some(payload)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_well_formed_lockfile() {
+        assert_eq!(
+            parse_lockfile("LeagueClient:12345:54321:s3cr3t-T0ken:https\n"),
+            Some((12345, "54321".to_string(), "s3cr3t-T0ken".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_or_malformed_lockfile() {
+        assert_eq!(parse_lockfile(""), None);
+        assert_eq!(parse_lockfile("LeagueClient:12345:54321"), None);
+        assert_eq!(parse_lockfile("LeagueClient:12345:54321:token"), None);
+        assert_eq!(parse_lockfile("LeagueClient:abc:54321:token:https"), None);
+    }
 }
@@ -1,21 +1,39 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod backend;
+mod capture;
+#[cfg(test)]
+mod fake_lcu;
 mod lcu;
 mod models;
 mod ui;
 mod utils;
 
+/// 测试中启动的模拟客户端（src/bin/mock_lcu）
+#[cfg(all(test, feature = "mock-lcu"))]
+#[path = "bin/mock_lcu"]
+#[allow(dead_code)]
+mod mock_lcu {
+    pub mod scenario;
+    pub mod server;
+}
+
 use anyhow::Result;
 use eframe::{NativeOptions, Renderer};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use backend::run_backend;
+use backend::{run_backend, run_replay};
 use ui::HexApp;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // 回放模式: hex_connector --replay data/captures/lcu-xxx.jsonl
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 3 && args[1] == "--replay" {
+        return run_replay(&args[2]).await;
+    }
+
     let (tx_gui, rx_gui) = crossbeam_channel::unbounded();
     let (tx_backend, rx_backend) = crossbeam_channel::unbounded();
     let shared_heroes = Arc::new(Mutex::new(HashMap::new()));
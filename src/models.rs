@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // --- Constants ---
 pub const TIER_MAP: [(&str, &str); 9] = [
@@ -14,11 +14,53 @@ pub const TIER_MAP: [(&str, &str); 9] = [
     ("CHALLENGER", "最强王者"),
 ];
 
+/// 召唤师技能ID与名称
+pub const SUMMONER_SPELLS: [(i32, &str); 11] = [
+    (4, "闪现"),
+    (11, "惩戒"),
+    (14, "点燃"),
+    (12, "传送"),
+    (7, "治疗"),
+    (3, "虚弱"),
+    (21, "屏障"),
+    (1, "净化"),
+    (6, "幽灵"),
+    (32, "标记"),
+    (13, "清晰术"),
+];
+
+/// 常用队列ID与名称
+pub const QUEUE_NAMES: [(i64, &str); 8] = [
+    (400, "匹配征召"),
+    (430, "匹配自选"),
+    (420, "单双排"),
+    (440, "灵活组排"),
+    (490, "快速匹配"),
+    (450, "大乱斗"),
+    (1700, "斗魂竞技场"),
+    (1900, "无限火力"),
+];
+
+/// 符文系ID与名称
+pub const RUNE_STYLES: [(i32, &str); 5] = [
+    (8000, "精密"),
+    (8100, "主宰"),
+    (8200, "巫术"),
+    (8300, "启迪"),
+    (8400, "坚决"),
+];
+
 // --- Data Structures ---
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct Settings {
     pub auto_accept: bool,
     pub auto_honor: bool,
+    /// 点赞对象的选择规则
+    pub honor_rule: HonorRule,
+    pub honor_category: HonorCategory,
+    /// 固定点赞名单（名称#标签 或 名称），按优先级排列
+    pub honor_players: Vec<String>,
     pub play_again: bool,
     pub auto_queue: bool,
     pub queue_delay: u64,
@@ -33,8 +75,78 @@ pub struct Settings {
     pub auto_ban_lock: bool,
     pub lock_time: u64,
     pub ban_time: u64,
-    pub sr_picks: HashMap<String, i32>,
-    pub sr_bans: HashMap<String, i32>,
+    /// 每个位置按优先级排列的预选英雄，前面的不可选时依次尝试后面的
+    #[serde(deserialize_with = "champion_lists")]
+    pub sr_picks: HashMap<String, Vec<i32>>,
+    /// 每个位置按优先级排列的禁用英雄
+    #[serde(deserialize_with = "champion_lists")]
+    pub sr_bans: HashMap<String, Vec<i32>>,
+    /// 大乱斗按评分表（data/tier_list.json / .csv）交换板凳席英雄
+    pub tier_scoring: bool,
+    /// 板凳席英雄比当前英雄至少高出多少分才交换
+    pub tier_threshold: f64,
+    /// 大乱斗当前英雄不想要时自动重随
+    pub auto_reroll: bool,
+    /// 重随时保留的英雄（秒选列表中的英雄同样保留）
+    pub reroll_keep_list: Vec<i32>,
+    /// 自动接受能换到预设列表中更靠前英雄的交换
+    pub trade_accept: bool,
+    /// 自动拒绝会换走预设英雄的交换
+    pub trade_decline: bool,
+    /// 主动向持有更想玩英雄的队友发起交换
+    pub trade_request: bool,
+    /// 收到选人顺序交换请求时的处理方式
+    pub pick_order_swap: SwapPolicy,
+    /// 收到位置交换请求时的处理方式
+    pub position_swap: SwapPolicy,
+    /// 在英雄选择聊天中自动发送消息
+    pub chat_enable: bool,
+    /// 进入英雄选择时发送的问候，为空则不发送
+    pub chat_greeting: String,
+    /// 分配到位置后发送的消息
+    pub chat_role: String,
+    /// 锁定英雄后发送的消息
+    pub chat_lock: String,
+    /// 进入英雄选择时查询队友段位与近期战绩
+    pub teammate_lookup: bool,
+    /// 查询的近期对局数
+    pub teammate_games: usize,
+    /// 斗魂竞技场自动预选
+    pub arena_enable: bool,
+    /// 斗魂竞技场自动禁用
+    pub arena_ban_enable: bool,
+    /// 斗魂竞技场自动锁定（选择与禁用）
+    pub arena_auto_lock: bool,
+    pub arena_lock_time: u64,
+    pub arena_ban_time: u64,
+    /// 斗魂竞技场按优先级排列的预选英雄
+    pub arena_picks: Vec<i32>,
+    /// 斗魂竞技场按优先级排列的禁用英雄
+    pub arena_bans: Vec<i32>,
+    /// 英雄确定后自动设置召唤师技能
+    pub auto_spells: bool,
+    /// 闪现放在 F 键（否则放在 D 键）
+    pub flash_on_f: bool,
+    /// 位置 -> 召唤师技能，大乱斗使用 "aram"
+    pub spells_by_role: HashMap<String, [i32; 2]>,
+    /// 英雄ID -> 召唤师技能，优先于位置配置
+    pub spells_by_champion: HashMap<i32, [i32; 2]>,
+    /// 英雄确定后自动应用符文预设（data/runes.json）
+    pub auto_runes: bool,
+    /// 英雄确定后写入装备方案（data/item_sets）
+    pub auto_item_sets: bool,
+    /// 英雄确定后自动选择皮肤
+    pub auto_skin: bool,
+    /// 英雄ID -> 收藏的皮肤/炫彩ID，按顺序选择第一个已拥有的
+    pub favourite_skins: HashMap<i32, Vec<i32>>,
+    /// 没有可用的收藏时随机选择已拥有的皮肤
+    pub random_skin: bool,
+    /// 客户端安装目录（为空时自动检测）
+    pub client_path: String,
+    /// 录制所有 LCU 请求与推送到 data/captures
+    pub capture: bool,
+    /// 按队列自动切换的方案，排在前面的优先
+    pub profiles: Vec<QueueProfile>,
 }
 
 impl Default for Settings {
@@ -42,6 +154,9 @@ impl Default for Settings {
         Self {
             auto_accept: true,
             auto_honor: true,
+            honor_rule: HonorRule::default(),
+            honor_category: HonorCategory::default(),
+            honor_players: Vec::new(),
             play_again: true,
             auto_queue: false,
             queue_delay: 3,
@@ -58,8 +173,380 @@ impl Default for Settings {
             ban_time: 2,
             sr_picks: HashMap::new(),
             sr_bans: HashMap::new(),
+            tier_scoring: false,
+            tier_threshold: 1.0,
+            auto_reroll: false,
+            reroll_keep_list: vec![],
+            trade_accept: false,
+            trade_decline: false,
+            trade_request: false,
+            pick_order_swap: SwapPolicy::Manual,
+            position_swap: SwapPolicy::Manual,
+            chat_enable: false,
+            chat_greeting: String::new(),
+            chat_role: "{role}".to_string(),
+            chat_lock: "锁定 {champion}".to_string(),
+            teammate_lookup: true,
+            teammate_games: 20,
+            arena_enable: true,
+            arena_ban_enable: true,
+            arena_auto_lock: false,
+            arena_lock_time: 3,
+            arena_ban_time: 2,
+            arena_picks: vec![],
+            arena_bans: vec![],
+            auto_spells: false,
+            flash_on_f: false,
+            spells_by_role: HashMap::from([
+                ("top".to_string(), [4, 12]),
+                ("jungle".to_string(), [4, 11]),
+                ("middle".to_string(), [4, 14]),
+                ("bottom".to_string(), [4, 7]),
+                ("utility".to_string(), [4, 14]),
+                ("aram".to_string(), [4, 32]),
+            ]),
+            spells_by_champion: HashMap::new(),
+            auto_runes: false,
+            auto_item_sets: false,
+            auto_skin: false,
+            favourite_skins: HashMap::new(),
+            random_skin: false,
+            client_path: String::new(),
+            capture: false,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// 匹配该队列的方案
+    pub fn profile_for(&self, queue_id: Option<i64>) -> Option<&QueueProfile> {
+        let queue_id = queue_id?;
+        self.profiles.iter().find(|p| p.queue_ids.contains(&queue_id))
+    }
+
+    /// 斗魂竞技场使用的设置：竞技场的列表与锁定时机映射到峡谷对应的字段，
+    /// 列表放在 "arena" 位置下；之后再叠加队列方案，方案中设置的字段优先
+    pub fn for_arena(&self, queue_id: Option<i64>) -> Settings {
+        let mut s = self.clone();
+        s.sr_enable = self.arena_enable;
+        s.sr_ban_enable = self.arena_ban_enable;
+        s.auto_lock = self.arena_auto_lock;
+        s.auto_ban_lock = self.arena_auto_lock;
+        s.lock_time = self.arena_lock_time;
+        s.ban_time = self.arena_ban_time;
+        s.sr_picks = HashMap::from([("arena".to_string(), self.arena_picks.clone())]);
+        s.sr_bans = HashMap::from([("arena".to_string(), self.arena_bans.clone())]);
+        if let Some(p) = self.profile_for(queue_id) {
+            s.apply_profile(p);
+        }
+        s
+    }
+
+    /// 应用队列方案后的设置，没有匹配的方案时与全局设置相同
+    pub fn for_queue(&self, queue_id: Option<i64>) -> Settings {
+        let mut s = self.clone();
+        if let Some(p) = self.profile_for(queue_id) {
+            s.apply_profile(p);
+        }
+        s
+    }
+
+    /// 用方案中设置了的字段覆盖当前设置
+    fn apply_profile(&mut self, p: &QueueProfile) {
+        if let Some(v) = p.sr_enable {
+            self.sr_enable = v;
+        }
+        if let Some(v) = p.auto_lock {
+            self.auto_lock = v;
+        }
+        if let Some(v) = p.lock_time {
+            self.lock_time = v;
+        }
+        if let Some(v) = p.sr_ban_enable {
+            self.sr_ban_enable = v;
+        }
+        if let Some(v) = p.auto_ban_lock {
+            self.auto_ban_lock = v;
+        }
+        if let Some(v) = p.ban_time {
+            self.ban_time = v;
+        }
+        if let Some(v) = &p.sr_picks {
+            self.sr_picks = v.clone();
+        }
+        if let Some(v) = &p.sr_bans {
+            self.sr_bans = v.clone();
+        }
+        if let Some(v) = p.aram_snipe {
+            self.aram_snipe = v;
+        }
+        if let Some(v) = &p.snipe_list {
+            self.snipe_list = v.clone();
         }
+        if let Some(v) = p.chat_enable {
+            self.chat_enable = v;
+        }
+    }
+}
+
+/// 收到选人顺序/位置交换请求时的处理方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SwapPolicy {
+    /// 不处理，由玩家决定
+    #[default]
+    Manual,
+    /// 选人顺序：换到更早的顺序时接受；位置：换到配置了预选英雄的位置时接受；其余拒绝
+    Smart,
+    Accept,
+    Decline,
+}
+
+impl SwapPolicy {
+    pub const ALL: [SwapPolicy; 4] = [
+        SwapPolicy::Manual,
+        SwapPolicy::Smart,
+        SwapPolicy::Accept,
+        SwapPolicy::Decline,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SwapPolicy::Manual => "手动",
+            SwapPolicy::Smart => "智能",
+            SwapPolicy::Accept => "全部接受",
+            SwapPolicy::Decline => "全部拒绝",
+        }
+    }
+}
+
+/// 自动点赞时选择队友的规则
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HonorRule {
+    /// 优先点赞一起开黑的好友，没有时点赞 KDA 最高的队友
+    Premade,
+    BestKda,
+    MostDamage,
+    /// 点赞固定名单中的第一个队友，没有时跳过
+    FixedList,
+    /// 跳过点赞；旧版本的自动点赞只会跳过，升级后的设置沿用这一行为
+    #[default]
+    OptOut,
+}
+
+impl HonorRule {
+    pub const ALL: [HonorRule; 5] = [
+        HonorRule::Premade,
+        HonorRule::BestKda,
+        HonorRule::MostDamage,
+        HonorRule::FixedList,
+        HonorRule::OptOut,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HonorRule::Premade => "开黑好友优先",
+            HonorRule::BestKda => "KDA 最高",
+            HonorRule::MostDamage => "伤害最高",
+            HonorRule::FixedList => "固定名单",
+            HonorRule::OptOut => "跳过点赞",
+        }
+    }
+}
+
+/// 点赞类别
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HonorCategory {
+    Cool,
+    Shotcaller,
+    #[default]
+    Heart,
+}
+
+impl HonorCategory {
+    pub const ALL: [HonorCategory; 3] =
+        [HonorCategory::Cool, HonorCategory::Shotcaller, HonorCategory::Heart];
+
+    /// 接口中的类别名
+    pub fn api_name(self) -> &'static str {
+        match self {
+            HonorCategory::Cool => "COOL",
+            HonorCategory::Shotcaller => "SHOTCALLER",
+            HonorCategory::Heart => "HEART",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HonorCategory::Cool => "保持冷静",
+            HonorCategory::Shotcaller => "出色指挥",
+            HonorCategory::Heart => "友善队友",
+        }
+    }
+}
+
+/// 队列方案：覆盖部分全局设置，None 表示沿用全局设置
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct QueueProfile {
+    pub name: String,
+    pub queue_ids: Vec<i64>,
+    pub sr_enable: Option<bool>,
+    pub auto_lock: Option<bool>,
+    pub lock_time: Option<u64>,
+    pub sr_ban_enable: Option<bool>,
+    pub auto_ban_lock: Option<bool>,
+    pub ban_time: Option<u64>,
+    pub sr_picks: Option<HashMap<String, Vec<i32>>>,
+    pub sr_bans: Option<HashMap<String, Vec<i32>>>,
+    pub aram_snipe: Option<bool>,
+    pub snipe_list: Option<Vec<i32>>,
+    pub chat_enable: Option<bool>,
+}
+
+/// 兼容旧版设置中每个位置只有一个英雄的格式
+fn champion_lists<'de, D>(deserializer: D) -> Result<HashMap<String, Vec<i32>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(i32),
+        Many(Vec<i32>),
     }
+
+    let raw = HashMap::<String, OneOrMany>::deserialize(deserializer)?;
+    Ok(raw
+        .into_iter()
+        .map(|(k, v)| match v {
+            OneOrMany::One(id) if id > 0 => (k, vec![id]),
+            OneOrMany::One(_) => (k, vec![]),
+            OneOrMany::Many(ids) => (k, ids),
+        })
+        .collect())
+}
+
+/// 符文预设，按英雄和位置匹配，位置为空表示任意位置
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RunePreset {
+    pub champion_id: i32,
+    pub position: String,
+    pub name: String,
+    pub primary_style_id: i32,
+    pub sub_style_id: i32,
+    /// 4 个主系符文、2 个副系符文和 3 个属性碎片
+    pub selected_perk_ids: Vec<i32>,
+}
+
+/// 英雄出装方案，来自 data/item_sets 下的 JSON 文件
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ItemBuild {
+    pub champion_id: i32,
+    pub title: String,
+    /// 适用的地图ID，为空表示全部地图
+    pub maps: Vec<i32>,
+    pub blocks: Vec<ItemBlock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ItemBlock {
+    pub name: String,
+    pub items: Vec<i32>,
+}
+
+/// 英雄选择中一个格子的状态
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DraftCell {
+    pub cell_id: i64,
+    /// 分配的位置，没有分路时为空
+    pub position: String,
+    /// 正在预选/意向的英雄，0 表示没有
+    pub hovered: i32,
+    /// 已锁定的英雄，0 表示尚未锁定
+    pub locked: i32,
+    pub is_local: bool,
+}
+
+/// 自己当前待完成的 action
+#[derive(Clone, Debug, PartialEq)]
+pub struct DraftAction {
+    /// "pick" 或 "ban"
+    pub kind: String,
+    pub in_progress: bool,
+    /// 助手高亮的英雄，0 表示还没有
+    pub target: i32,
+    /// 计划自动锁定/禁用的时间点
+    pub lock_at: Option<std::time::Instant>,
+}
+
+/// 英雄选择快照，供界面展示
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DraftSnapshot {
+    pub my_team: Vec<DraftCell>,
+    pub their_team: Vec<DraftCell>,
+    pub my_bans: Vec<i32>,
+    pub their_bans: Vec<i32>,
+    pub pending: Option<DraftAction>,
+    /// 计时器阶段 (PLANNING / BAN_PICK / FINALIZATION)
+    pub phase: String,
+    /// 当前阶段结束的时间点
+    pub phase_ends_at: Option<std::time::Instant>,
+}
+
+/// 队友的段位与近期战绩
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TeammateInfo {
+    pub cell_id: i64,
+    pub position: String,
+    /// 召唤师名称，查询不到时为空
+    pub name: String,
+    /// 单双排段位，如 "荣耀黄金 II"
+    pub rank: String,
+    pub ranked_wins: i64,
+    pub ranked_losses: i64,
+    /// 近期对局数与其中的胜场
+    pub games: usize,
+    pub recent_wins: usize,
+    /// 近期使用最多的英雄（最多 3 个）
+    pub main_champions: Vec<i32>,
+    /// 连胜为正、连败为负
+    pub streak: i32,
+    /// 战绩被隐藏或查询失败
+    pub hidden: bool,
+}
+
+/// 本地保存的一局对局
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GameRecord {
+    pub game_id: i64,
+    /// 记录时间 (RFC 3339)
+    pub time: String,
+    /// 玩家名称 (名称#标签)
+    pub player: String,
+    pub queue_id: Option<i64>,
+    pub champion_id: i32,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    pub win: bool,
+    /// 对局时长（秒）
+    pub duration: i64,
+    /// 排位分变化，非排位或取不到时为 None
+    pub lp_change: Option<i64>,
+    pub teammates: Vec<GameTeammate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GameTeammate {
+    pub name: String,
+    pub champion_id: i32,
 }
 
 #[derive(Clone)]
@@ -73,6 +560,30 @@ pub enum GuiMsg {
     Status(bool),
     BenchUpdate(Vec<i32>),
     LoadedData(String),
+    RunePresets(Vec<RunePreset>),
+    /// 当前队列与生效的方案名称
+    ActiveProfile {
+        queue_id: Option<i64>,
+        profile: Option<String>,
+    },
+    /// 当前英雄已拥有的皮肤与炫彩 (ID, 名称)
+    SkinCarousel {
+        champion_id: i32,
+        skins: Vec<(i32, String)>,
+    },
+    /// 当前账号可选/可禁用的英雄（None 表示获取失败）
+    ChampionAvailability {
+        pickable: Option<HashSet<i32>>,
+        bannable: Option<HashSet<i32>>,
+    },
+    /// 大乱斗剩余重随次数
+    RerollsLeft(i64),
+    /// 英雄选择的实时状态，离开英雄选择时为 None
+    Draft(Option<DraftSnapshot>),
+    /// 队友的段位与近期战绩
+    Teammates(Vec<TeammateInfo>),
+    /// 一局对局已写入本地战绩
+    GameRecorded(GameRecord),
 }
 
 pub enum BackendMsg {
@@ -80,6 +591,21 @@ pub enum BackendMsg {
     SaveSettings(Settings),
     UpdateRank,
     ForceReconnect,
+    SaveRunePresets(Vec<RunePreset>),
+    /// 把客户端当前符文页保存为该英雄/位置的预设
+    ImportRunePage { champion_id: i32, position: String },
+    /// 删除助手写入的所有装备方案
+    CleanupItemSets,
+    /// 皮肤收藏选择展开/收起
+    SkinPicker(bool),
+    /// 导出上一局的结算数据 (CSV + JSON)
+    ExportLastGame,
+    /// 导出该账号本地战绩中日期范围内的对局 (CSV + JSON)
+    ExportHistory {
+        puuid: String,
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -94,8 +620,179 @@ pub struct Hero {
 #[derive(Clone, Default)]
 pub struct ActionState {
     pub hovered: bool,
+    /// 最近一次由助手高亮的英雄
+    pub target: i32,
     pub completed: bool,
     pub last_act_time: Option<std::time::Instant>,
     /// 计划执行锁定的时间点（当 isInProgress 首次为 true 时设置）
     pub lock_scheduled_at: Option<std::time::Instant>,
+    /// 最近一次请求失败的信息（用于避免重复刷屏）
+    pub last_error: Option<String>,
+}
+
+/// 一次英雄选择期间的状态，进入新的英雄选择时重置
+#[derive(Default)]
+pub struct ChampSelectState {
+    /// ActionID -> 处理状态
+    pub handled_actions: HashMap<i64, ActionState>,
+    pub last_bench_ids: Vec<i32>,
+    /// 是否已查询到（或已放弃查询）可选/可禁用英雄
+    pub availability_checked: bool,
+    /// 查询可选/可禁用英雄的次数，请求失败时稍后重试
+    pub availability_attempts: u32,
+    pub availability_retry_at: Option<std::time::Instant>,
+    /// 当前账号可选的英雄（拥有或本周免费），None 表示未知
+    pub pickable: Option<HashSet<i32>>,
+    /// 当前可禁用的英雄，None 表示未知
+    pub bannable: Option<HashSet<i32>>,
+    /// 已应用召唤师技能、符文、装备方案的英雄，英雄变化后重新应用；
+    /// 各步骤分别记录，失败的步骤稍后重试
+    pub spells_champion: i32,
+    pub runes_champion: i32,
+    pub item_sets_champion: i32,
+    /// 英雄ID -> (应用配置的次数, 上次应用的时间)，全部成功后清除
+    pub loadout_attempts: HashMap<i32, (u32, std::time::Instant)>,
+    /// 已处理皮肤的英雄
+    pub skin_champion: i32,
+    /// 英雄ID -> (读取皮肤列表的次数, 上次读取的时间)，成功后清除
+    pub skin_attempts: HashMap<i32, (u32, std::time::Instant)>,
+    /// 界面的皮肤收藏选择是否展开（需要当前英雄的皮肤列表）
+    pub skin_picker: bool,
+    /// 是否已判断过游戏模式
+    pub mode_checked: bool,
+    /// 当前是否为斗魂竞技场
+    pub arena: bool,
+    /// 大乱斗评分表，每次英雄选择读取一次
+    pub tier_scores: Option<HashMap<i32, f64>>,
+    /// 交换ID -> 上次看到的状态，用于记录状态变化
+    pub trade_states: HashMap<i64, String>,
+    /// "类型/交换ID" -> 上次看到的选人顺序/位置交换状态
+    pub swap_states: HashMap<String, String>,
+    /// 已主动发起过的交换
+    pub requested_trades: HashSet<i64>,
+    /// 已发送的聊天消息类型（问候、位置、锁定的英雄ID）
+    pub chat_sent: HashSet<String>,
+    /// 自己的段位文字，None 表示未查询
+    pub chat_rank: Option<String>,
+    /// 是否已查询过重随次数
+    pub reroll_checked: bool,
+    /// 剩余重随次数，None 表示未知
+    pub rerolls_left: Option<i64>,
+    /// 上一次重随时的英雄，会话更新前不再重复重随
+    pub rerolled_from: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, queue_ids: Vec<i64>) -> QueueProfile {
+        QueueProfile {
+            name: name.into(),
+            queue_ids,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn settings_without_honor_rule_keep_opting_out() {
+        let old = r#"{ "auto_accept": true, "auto_honor": true, "play_again": false }"#;
+        let s: Settings = serde_json::from_str(old).unwrap();
+
+        assert!(s.auto_honor && !s.play_again);
+        assert_eq!(s.honor_rule, HonorRule::OptOut);
+        assert!(s.honor_players.is_empty());
+    }
+
+    #[test]
+    fn queue_without_profile_keeps_global_settings() {
+        let mut s = Settings {
+            lock_time: 4,
+            ..Default::default()
+        };
+        s.profiles.push(QueueProfile {
+            lock_time: Some(9),
+            ..profile("大乱斗", vec![450])
+        });
+
+        assert!(s.profile_for(Some(420)).is_none());
+        assert!(s.profile_for(None).is_none());
+        assert_eq!(s.for_queue(Some(420)).lock_time, 4);
+        assert_eq!(s.for_queue(None).lock_time, 4);
+    }
+
+    #[test]
+    fn profile_overrides_only_fields_it_sets() {
+        let mut s = Settings {
+            auto_lock: false,
+            lock_time: 4,
+            ban_time: 2,
+            chat_enable: true,
+            ..Default::default()
+        };
+        s.sr_picks.insert("middle".into(), vec![103]);
+        s.profiles.push(QueueProfile {
+            auto_lock: Some(true),
+            lock_time: Some(9),
+            sr_bans: Some(HashMap::from([("top".to_string(), vec![86])])),
+            ..profile("单双排", vec![420])
+        });
+
+        let ranked = s.for_queue(Some(420));
+
+        assert!(ranked.auto_lock);
+        assert_eq!(ranked.lock_time, 9);
+        assert_eq!(ranked.sr_bans["top"], vec![86]);
+        assert_eq!(ranked.ban_time, 2);
+        assert!(ranked.chat_enable);
+        assert_eq!(ranked.sr_picks["middle"], vec![103]);
+    }
+
+    #[test]
+    fn first_matching_profile_wins() {
+        let mut s = Settings::default();
+        s.profiles.push(QueueProfile {
+            lock_time: Some(5),
+            ..profile("排位", vec![420, 440])
+        });
+        s.profiles.push(QueueProfile {
+            lock_time: Some(8),
+            ..profile("灵活", vec![440])
+        });
+
+        assert_eq!(s.profile_for(Some(440)).map(|p| p.name.as_str()), Some("排位"));
+        assert_eq!(s.for_queue(Some(440)).lock_time, 5);
+    }
+
+    #[test]
+    fn arena_queue_profile_overrides_arena_defaults() {
+        let mut s = Settings {
+            arena_auto_lock: false,
+            arena_lock_time: 3,
+            arena_picks: vec![157],
+            ..Default::default()
+        };
+        s.profiles.push(QueueProfile {
+            name: "竞技场".into(),
+            queue_ids: vec![1700],
+            auto_lock: Some(true),
+            lock_time: Some(8),
+            sr_picks: Some(HashMap::from([("arena".to_string(), vec![7])])),
+            ..Default::default()
+        });
+
+        let arena = s.for_queue(Some(1700)).for_arena(Some(1700));
+
+        assert!(arena.auto_lock);
+        assert_eq!(arena.lock_time, 8);
+        assert_eq!(arena.sr_picks["arena"], vec![7]);
+        // 方案未设置的字段仍使用竞技场设置
+        assert_eq!(arena.sr_enable, s.arena_enable);
+        assert_eq!(arena.ban_time, s.arena_ban_time);
+        assert_eq!(arena.sr_bans["arena"], Vec::<i32>::new());
+
+        let other = s.for_arena(Some(420));
+        assert!(!other.auto_lock);
+        assert_eq!((other.lock_time, other.sr_picks["arena"].clone()), (3, vec![157]));
+    }
 }
@@ -1,14 +1,21 @@
 use chrono::Local;
 use eframe::{egui, App};
 use egui::{Color32, FontData, FontDefinitions, FontFamily, Vec2};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::models::{BackendMsg, GuiMsg, Hero, Settings, TIER_MAP};
-use crate::utils::{load_settings, lookup_hero_id, lookup_hero_image_by_text, lookup_hero_name_by_text, IMG_DIR};
+use crate::models::{
+    BackendMsg, DraftCell, DraftSnapshot, GameRecord, GuiMsg, Hero, HonorCategory, HonorRule,
+    QueueProfile, RunePreset, Settings, SwapPolicy, TeammateInfo, QUEUE_NAMES, RUNE_STYLES,
+    SUMMONER_SPELLS, TIER_MAP,
+};
+use crate::utils::{
+    history_accounts, history_path, load_game_history, load_rune_presets, load_settings, load_tier_scores, lookup_hero_id, lookup_hero_ids,
+    lookup_hero_image_by_id, lookup_hero_name_by_id, spell_name, IMG_DIR,
+};
 
 pub fn configure_visuals(ctx: &egui::Context) {
     let mut visuals = egui::Visuals::dark();
@@ -69,6 +76,48 @@ pub struct HexApp {
     search_result: Vec<Hero>,
     sr_pick_text: HashMap<String, String>,
     sr_ban_text: HashMap<String, String>,
+    arena_pick_text: String,
+    arena_ban_text: String,
+    reroll_keep_text: String,
+    /// 固定点赞名单，逗号分隔
+    honor_players_text: String,
+    /// 大乱斗剩余重随次数
+    rerolls_left: Option<i64>,
+    /// 当前英雄选择的状态
+    draft: Option<DraftSnapshot>,
+    /// 最近一次英雄选择中队友的战绩
+    teammates: Vec<TeammateInfo>,
+    /// 本地战绩：已保存的账号 (puuid, 名称)、当前查看的账号及其对局
+    history_accounts: Vec<(String, String)>,
+    history_account: Option<String>,
+    history: Vec<GameRecord>,
+    history_loaded: bool,
+    /// 导出战绩的日期范围 (YYYY-MM-DD)
+    export_from: String,
+    export_to: String,
+    /// 大乱斗评分表
+    tier_scores: HashMap<i32, f64>,
+    /// 评分表中无法识别的英雄名
+    tier_unresolved: Vec<String>,
+    /// 最近一次英雄选择中账号可选/可禁用的英雄
+    pickable: Option<HashSet<i32>>,
+    bannable: Option<HashSet<i32>>,
+    spell_champ_input: String,
+    rune_presets: Vec<RunePreset>,
+    rune_champ_input: String,
+    rune_position: String,
+    /// 符文ID编辑框的内容（英雄ID, 位置）-> 文本
+    rune_perk_text: HashMap<(i32, String), String>,
+    /// 最近一次英雄选择中我方英雄已拥有的皮肤
+    skin_carousel: Option<(i32, Vec<(i32, String)>)>,
+    skin_names: HashMap<i32, String>,
+    /// 皮肤收藏选择是否展开，展开时后台才读取皮肤列表
+    skin_picker_open: bool,
+    /// 当前队列与生效的方案
+    active_queue: Option<i64>,
+    active_profile: Option<String>,
+    /// 方案中英雄列表编辑框的内容
+    profile_text: HashMap<String, String>,
     image_cache: HashMap<String, egui::TextureHandle>,
 }
 
@@ -121,6 +170,36 @@ impl HexApp {
             search_result: vec![],
             sr_pick_text: HashMap::new(),
             sr_ban_text: HashMap::new(),
+            arena_pick_text: String::new(),
+            arena_ban_text: String::new(),
+            reroll_keep_text: String::new(),
+            honor_players_text: String::new(),
+            rerolls_left: None,
+            draft: None,
+            teammates: vec![],
+            history_accounts: vec![],
+            history_account: None,
+            history: vec![],
+            history_loaded: false,
+            export_from: (Local::now() - chrono::Duration::days(30))
+                .format("%Y-%m-%d")
+                .to_string(),
+            export_to: Local::now().format("%Y-%m-%d").to_string(),
+            tier_scores: HashMap::new(),
+            tier_unresolved: Vec::new(),
+            pickable: None,
+            bannable: None,
+            spell_champ_input: String::new(),
+            rune_presets: load_rune_presets(),
+            rune_champ_input: String::new(),
+            rune_position: String::new(),
+            rune_perk_text: HashMap::new(),
+            skin_carousel: None,
+            skin_names: HashMap::new(),
+            skin_picker_open: false,
+            active_queue: None,
+            active_profile: None,
+            profile_text: HashMap::new(),
             image_cache: HashMap::new(),
         }
     }
@@ -133,23 +212,876 @@ impl HexApp {
         let pos_keys = vec!["top", "jungle", "middle", "bottom", "utility"];
         for k in pos_keys {
             // Always update to reflect loaded settings/names
-            let pick_name = self
+            let pick_names: Vec<String> = self
                 .settings
                 .sr_picks
                 .get(k)
-                .and_then(|id| map.get(id))
+                .into_iter()
+                .flatten()
+                .filter_map(|id| map.get(id))
                 .map(|h| h.name.clone())
-                .unwrap_or_default();
-            self.sr_pick_text.insert(k.to_string(), pick_name);
+                .collect();
+            self.sr_pick_text.insert(k.to_string(), pick_names.join(" "));
 
-            let ban_name = self
+            let ban_names: Vec<String> = self
                 .settings
                 .sr_bans
                 .get(k)
-                .and_then(|id| map.get(id))
+                .into_iter()
+                .flatten()
+                .filter_map(|id| map.get(id))
                 .map(|h| h.name.clone())
-                .unwrap_or_default();
-            self.sr_ban_text.insert(k.to_string(), ban_name);
+                .collect();
+            self.sr_ban_text.insert(k.to_string(), ban_names.join(" "));
+        }
+        drop(map);
+        self.arena_pick_text = hero_names(&self.heroes, &self.settings.arena_picks);
+        self.arena_ban_text = hero_names(&self.heroes, &self.settings.arena_bans);
+        self.reroll_keep_text = hero_names(&self.heroes, &self.settings.reroll_keep_list);
+        self.honor_players_text = self.settings.honor_players.join(", ");
+        (self.tier_scores, self.tier_unresolved) = load_tier_scores(&self.heroes);
+    }
+
+    /// 预设列表中的英雄头像，当前账号无法使用时变暗并提示
+    fn hero_icon(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, id: i32, available: bool) {
+        let img_name = lookup_hero_image_by_id(&self.heroes, id);
+        let Some(texture) = self.get_image(ctx, &img_name) else {
+            // 图片加载失败或正在下载时，显示占位
+            ui.label("...");
+            return;
+        };
+        let image = egui::Image::new((texture.id(), Vec2::splat(20.0)));
+        if available {
+            ui.add(image);
+        } else {
+            ui.add(image.tint(Color32::from_rgb(120, 50, 50)))
+                .on_hover_text("当前账号无法使用该英雄");
+        }
+    }
+
+    /// 英雄选择实时状态：双方阵容、禁用、计时器以及助手接下来的操作
+    fn draft_card(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some(draft) = self.draft.clone() else {
+            return;
+        };
+        let now = Instant::now();
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("英雄选择")
+                    .color(Color32::from_rgb(200, 170, 110))
+                    .strong(),
+            );
+            let phase = match draft.phase.as_str() {
+                "PLANNING" => "意向阶段",
+                "BAN_PICK" => "禁用/选择",
+                "FINALIZATION" => "准备阶段",
+                other => other,
+            };
+            ui.colored_label(Color32::GRAY, phase);
+            if let Some(end) = draft.phase_ends_at {
+                let left = end.saturating_duration_since(now).as_secs_f32();
+                ui.colored_label(Color32::from_rgb(10, 203, 230), format!("{:.0}s", left.ceil()));
+            }
+        });
+
+        for (label, bans) in [("我方禁用", &draft.my_bans), ("敌方禁用", &draft.their_bans)] {
+            if bans.is_empty() {
+                continue;
+            }
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                ui.label(format!("{}:", label));
+                for &id in bans {
+                    self.draft_icon(ui, ctx, id, 20.0, Color32::from_gray(110));
+                }
+            });
+        }
+
+        ui.columns(2, |cols| {
+            for (ui, team) in cols.iter_mut().zip([&draft.my_team, &draft.their_team]) {
+                for cell in team {
+                    ui.horizontal(|ui| self.draft_cell(ui, ctx, cell));
+                }
+            }
+        });
+
+        if let Some(action) = &draft.pending {
+            let what = if action.kind == "ban" { "禁用" } else { "选择" };
+            let text = if !action.in_progress {
+                format!("等待轮到自己{}", what)
+            } else if action.target <= 0 {
+                format!("轮到自己{}，没有可用的预设英雄", what)
+            } else {
+                let name = lookup_hero_name_by_id(&self.heroes, action.target);
+                match action.lock_at {
+                    Some(at) if at > now => format!(
+                        "{} {}，{:.1}s 后自动锁定",
+                        what,
+                        name,
+                        (at - now).as_secs_f32()
+                    ),
+                    Some(_) => format!("{} {}，正在锁定", what, name),
+                    None => format!("已预选 {}", name),
+                }
+            };
+            ui.colored_label(Color32::from_rgb(10, 203, 230), text);
+        }
+    }
+
+    /// 队友段位、胜率、常用英雄与连胜/连败
+    fn teammates_card(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("队友战绩")
+                    .color(Color32::from_rgb(200, 170, 110))
+                    .strong(),
+            );
+            let mut changed = ui
+                .checkbox(&mut self.settings.teammate_lookup, "进入选人时查询")
+                .changed();
+            ui.label("近期对局:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.settings.teammate_games).range(5..=50))
+                .changed();
+            if changed {
+                self.trigger_save();
+            }
+        });
+        if self.teammates.is_empty() {
+            ui.colored_label(Color32::GRAY, "等待英雄选择...");
+            return;
+        }
+        egui::Grid::new("teammates_grid").striped(true).show(ui, |ui| {
+            for t in self.teammates.clone() {
+                ui.label(position_label(&t.position));
+                ui.label(if t.name.is_empty() { "匿名" } else { &t.name });
+                if t.hidden && t.games == 0 {
+                    ui.colored_label(Color32::GRAY, "战绩隐藏");
+                    ui.end_row();
+                    continue;
+                }
+                let ranked = t.ranked_wins + t.ranked_losses;
+                if ranked > 0 {
+                    ui.label(format!(
+                        "{} · 胜率 {:.0}% ({}场)",
+                        t.rank,
+                        t.ranked_wins as f64 * 100.0 / ranked as f64,
+                        ranked
+                    ));
+                } else {
+                    ui.label(&t.rank);
+                }
+                if t.games > 0 {
+                    ui.label(format!("近{}场 {}胜", t.games, t.recent_wins));
+                } else {
+                    ui.label("");
+                }
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 2.0;
+                    for &id in &t.main_champions {
+                        self.draft_icon(ui, ctx, id, 20.0, Color32::WHITE);
+                    }
+                });
+                match t.streak {
+                    n if n >= 2 => {
+                        ui.colored_label(Color32::from_rgb(80, 200, 120), format!("{}连胜", n))
+                    }
+                    n if n <= -2 => {
+                        ui.colored_label(Color32::from_rgb(220, 80, 80), format!("{}连败", -n))
+                    }
+                    _ => ui.label(""),
+                };
+                ui.end_row();
+            }
+        });
+    }
+
+    fn reload_history(&mut self) {
+        self.history = match &self.history_account {
+            Some(puuid) => load_game_history(&history_path(puuid)),
+            None => Vec::new(),
+        };
+    }
+
+    /// 本地保存的对局记录（data/history）
+    fn history_card(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if !self.history_loaded {
+            self.history_loaded = true;
+            self.history_accounts = history_accounts();
+            if self.history_account.is_none() {
+                self.history_account = self.history_accounts.first().map(|(p, _)| p.clone());
+            }
+            self.reload_history();
+        }
+        ui.horizontal(|ui| {
+            let selected = self
+                .history_accounts
+                .iter()
+                .find(|(p, _)| Some(p) == self.history_account.as_ref())
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| "无记录".to_string());
+            let mut changed = false;
+            egui::ComboBox::from_id_salt("history_account")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (puuid, name) in &self.history_accounts {
+                        changed |= ui
+                            .selectable_value(&mut self.history_account, Some(puuid.clone()), name)
+                            .clicked();
+                    }
+                });
+            if ui.button("刷新").clicked() {
+                self.history_loaded = false;
+            }
+            if changed {
+                self.reload_history();
+            }
+            if !self.history.is_empty() {
+                let wins = self.history.iter().filter(|r| r.win).count();
+                ui.label(format!(
+                    "共 {} 局 · 胜率 {:.0}%",
+                    self.history.len(),
+                    wins as f64 * 100.0 / self.history.len() as f64
+                ));
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("导出上一局").on_hover_text("导出结算数据到 data/exports").clicked() {
+                let _ = self.tx_to_backend.send(BackendMsg::ExportLastGame);
+            }
+            ui.separator();
+            ui.add(egui::TextEdit::singleline(&mut self.export_from).desired_width(80.0));
+            ui.label("至");
+            ui.add(egui::TextEdit::singleline(&mut self.export_to).desired_width(80.0));
+            let parse = |s: &str| chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok();
+            let range = parse(&self.export_from).zip(parse(&self.export_to));
+            let enabled = range.is_some() && self.history_account.is_some();
+            if ui.add_enabled(enabled, egui::Button::new("导出范围")).clicked() {
+                if let (Some((from, to)), Some(puuid)) = (range, self.history_account.clone()) {
+                    let _ = self
+                        .tx_to_backend
+                        .send(BackendMsg::ExportHistory { puuid, from, to });
+                }
+            }
+        });
+        if self.history.is_empty() {
+            ui.colored_label(Color32::GRAY, "对局结束后会自动记录");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("history_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("history_grid").striped(true).show(ui, |ui| {
+                    for r in self.history.clone() {
+                        let time = chrono::DateTime::parse_from_rfc3339(&r.time)
+                            .map(|t| t.format("%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        ui.label(time);
+                        ui.label(r.queue_id.map(queue_name).unwrap_or_default());
+                        ui.horizontal(|ui| {
+                            self.draft_icon(ui, ctx, r.champion_id, 20.0, Color32::WHITE)
+                        });
+                        ui.label(format!("{}/{}/{}", r.kills, r.deaths, r.assists));
+                        if r.win {
+                            ui.colored_label(Color32::from_rgb(80, 200, 120), "胜利");
+                        } else {
+                            ui.colored_label(Color32::from_rgb(220, 80, 80), "失败");
+                        }
+                        ui.label(format!("{}:{:02}", r.duration / 60, r.duration % 60));
+                        let lp = r.lp_change.map(|lp| format!("{:+} LP", lp));
+                        ui.label(lp.unwrap_or_default());
+                        let names: Vec<String> = r
+                            .teammates
+                            .iter()
+                            .map(|t| {
+                                let champion = lookup_hero_name_by_id(&self.heroes, t.champion_id);
+                                format!("{} ({})", t.name, champion)
+                            })
+                            .collect();
+                        if names.is_empty() {
+                            ui.label("");
+                        } else {
+                            ui.label("队友").on_hover_text(names.join("\n"));
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    /// 英雄选择面板中的一个格子：头像（预选时变暗）+ 位置
+    fn draft_cell(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, cell: &DraftCell) {
+        match (cell.locked, cell.hovered) {
+            (id, _) if id > 0 => self.draft_icon(ui, ctx, id, 28.0, Color32::WHITE),
+            (_, id) if id > 0 => self.draft_icon(ui, ctx, id, 28.0, Color32::from_gray(100)),
+            _ => {
+                ui.add_sized(Vec2::splat(28.0), egui::Label::new("?"));
+            }
+        }
+        let mut text = egui::RichText::new(position_label(&cell.position));
+        if cell.is_local {
+            text = text.color(Color32::from_rgb(10, 203, 230)).strong();
+        }
+        ui.label(text);
+    }
+
+    fn draft_icon(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        id: i32,
+        size: f32,
+        tint: Color32,
+    ) {
+        let img_name = lookup_hero_image_by_id(&self.heroes, id);
+        let name = lookup_hero_name_by_id(&self.heroes, id);
+        match self.get_image(ctx, &img_name) {
+            Some(texture) => {
+                let image = egui::Image::new((texture.id(), Vec2::splat(size))).tint(tint);
+                ui.add(image).on_hover_text(name);
+            }
+            None => {
+                ui.label(name);
+            }
+        }
+    }
+
+    /// 斗魂竞技场：独立的预选/禁用列表与锁定时机，会避开双人队友的意向英雄
+    fn arena_card(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.set_width(ui.available_width());
+        ui.label(
+            egui::RichText::new("斗魂竞技场")
+                .color(Color32::from_rgb(200, 170, 110))
+                .strong(),
+        );
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut self.settings.arena_enable, "预选").changed();
+            changed |= ui.checkbox(&mut self.settings.arena_ban_enable, "禁用").changed();
+            changed |= ui.checkbox(&mut self.settings.arena_auto_lock, "锁定").changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("选择倒计时:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.settings.arena_lock_time).range(1..=30).suffix("s"))
+                .changed();
+            ui.label("禁用倒计时:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.settings.arena_ban_time).range(1..=30).suffix("s"))
+                .changed();
+        });
+        egui::Grid::new("arena_grid").show(ui, |ui| {
+            ui.label("预选");
+            changed |= hero_list_edit(
+                ui,
+                &self.heroes,
+                &mut self.arena_pick_text,
+                &mut self.settings.arena_picks,
+            );
+            ui.end_row();
+            ui.label("");
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                for id in self.settings.arena_picks.clone() {
+                    let playable = self.pickable.as_ref().is_none_or(|p| p.contains(&id));
+                    self.hero_icon(ui, ctx, id, playable);
+                }
+            });
+            ui.end_row();
+            ui.label("禁用");
+            changed |= hero_list_edit(
+                ui,
+                &self.heroes,
+                &mut self.arena_ban_text,
+                &mut self.settings.arena_bans,
+            );
+            ui.end_row();
+            ui.label("");
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                for id in self.settings.arena_bans.clone() {
+                    let bannable = self.bannable.as_ref().is_none_or(|b| b.contains(&id));
+                    self.hero_icon(ui, ctx, id, bannable);
+                }
+            });
+            ui.end_row();
+        });
+        self.availability_hint(ui);
+        if changed {
+            self.trigger_save();
+        }
+    }
+
+    /// 尚未获取账号可用英雄时提示：进入英雄选择后才会标出无法使用的预选
+    fn availability_hint(&self, ui: &mut egui::Ui) {
+        if self.pickable.is_none() || self.bannable.is_none() {
+            ui.colored_label(Color32::GRAY, "进入英雄选择后会标出当前账号无法使用的英雄");
+        }
+    }
+
+    /// 自动点赞的规则、类别与固定名单，返回是否修改
+    fn honor_card(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("点赞:");
+            let rule = &mut self.settings.honor_rule;
+            egui::ComboBox::from_id_salt("honor_rule")
+                .width(90.0)
+                .selected_text(rule.label())
+                .show_ui(ui, |ui| {
+                    for r in HonorRule::ALL {
+                        changed |= ui.selectable_value(rule, r, r.label()).clicked();
+                    }
+                })
+                .response
+                .on_hover_text("开黑好友优先：没有好友同队时点赞 KDA 最高的队友");
+            if *rule == HonorRule::OptOut {
+                return;
+            }
+            let category = &mut self.settings.honor_category;
+            egui::ComboBox::from_id_salt("honor_category")
+                .width(70.0)
+                .selected_text(category.label())
+                .show_ui(ui, |ui| {
+                    for c in HonorCategory::ALL {
+                        changed |= ui.selectable_value(category, c, c.label()).clicked();
+                    }
+                });
+        });
+        if self.settings.honor_rule == HonorRule::FixedList {
+            ui.horizontal(|ui| {
+                ui.label("名单:");
+                let edit = egui::TextEdit::singleline(&mut self.honor_players_text)
+                    .hint_text("名称#标签，逗号分隔，按优先级排列");
+                if ui.add(edit).changed() {
+                    self.settings.honor_players = self
+                        .honor_players_text
+                        .split([',', '，', ';'])
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                    changed = true;
+                }
+            });
+        }
+        changed
+    }
+
+    /// 英雄选择聊天的消息模板
+    fn chat_card(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("选人聊天")
+                    .color(Color32::from_rgb(200, 170, 110))
+                    .strong(),
+            );
+            if ui
+                .checkbox(&mut self.settings.chat_enable, "启用")
+                .on_hover_text("可在队列方案中按队列开关")
+                .changed()
+            {
+                self.trigger_save();
+            }
+        });
+        let mut changed = false;
+        egui::Grid::new("chat_grid").show(ui, |ui| {
+            let rows = [
+                ("问候", &mut self.settings.chat_greeting, "进入选人时发送"),
+                ("位置", &mut self.settings.chat_role, "{role}"),
+                ("锁定", &mut self.settings.chat_lock, "锁定 {champion}"),
+            ];
+            for (label, text, hint) in rows {
+                ui.label(label);
+                changed |= ui
+                    .add(egui::TextEdit::singleline(text).desired_width(200.0).hint_text(hint))
+                    .changed();
+                ui.end_row();
+            }
+        });
+        ui.colored_label(
+            Color32::GRAY,
+            "占位符: {role} 位置, {champion} 英雄, {rank} 段位；留空则不发送",
+        );
+        if changed {
+            self.trigger_save();
+        }
+    }
+
+    /// 队列方案：按队列覆盖部分全局设置，未勾选的项沿用全局设置
+    fn profiles_card(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("队列方案")
+                    .color(Color32::from_rgb(200, 170, 110))
+                    .strong(),
+            );
+            if ui.small_button("新建").clicked() {
+                self.settings.profiles.push(QueueProfile {
+                    name: format!("方案{}", self.settings.profiles.len() + 1),
+                    ..Default::default()
+                });
+                self.trigger_save();
+            }
+        });
+
+        let mut changed = false;
+        let mut rm = None;
+        let global = self.settings.clone();
+        for (i, profile) in self.settings.profiles.iter_mut().enumerate() {
+            let active = self.active_profile.as_deref() == Some(profile.name.as_str());
+            let title = if active {
+                format!("{} (生效中)", profile.name)
+            } else {
+                profile.name.clone()
+            };
+            egui::CollapsingHeader::new(title)
+                .id_salt(("profile", i))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("名称:");
+                        changed |= ui.text_edit_singleline(&mut profile.name).changed();
+                        if ui.button("删除").clicked() {
+                            rm = Some(i);
+                        }
+                    });
+                    ui.horizontal_wrapped(|ui| {
+                        for (id, name) in QUEUE_NAMES {
+                            let mut on = profile.queue_ids.contains(&id);
+                            if ui.checkbox(&mut on, name).changed() {
+                                if on {
+                                    profile.queue_ids.push(id);
+                                } else {
+                                    profile.queue_ids.retain(|&q| q != id);
+                                }
+                                changed = true;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        changed |= tri_state(ui, ("p_pick", i), "预选", &mut profile.sr_enable);
+                        changed |= tri_state(ui, ("p_lock", i), "锁定", &mut profile.auto_lock);
+                        changed |= opt_secs(ui, "剩余", &mut profile.lock_time, global.lock_time);
+                    });
+                    ui.horizontal(|ui| {
+                        changed |= tri_state(ui, ("p_ban", i), "禁用", &mut profile.sr_ban_enable);
+                        changed |=
+                            tri_state(ui, ("p_ban_lock", i), "锁定", &mut profile.auto_ban_lock);
+                        changed |= opt_secs(ui, "剩余", &mut profile.ban_time, global.ban_time);
+                    });
+                    ui.horizontal(|ui| {
+                        changed |=
+                            tri_state(ui, ("p_snipe", i), "大乱斗秒选", &mut profile.aram_snipe);
+                        changed |=
+                            tri_state(ui, ("p_chat", i), "选人聊天", &mut profile.chat_enable);
+                    });
+
+                    let lists = [
+                        ("pick", "单独的预选列表", &mut profile.sr_picks, &global.sr_picks),
+                        ("ban", "单独的禁用列表", &mut profile.sr_bans, &global.sr_bans),
+                    ];
+                    for (kind, label, list, global_list) in lists {
+                        let mut on = list.is_some();
+                        if ui.checkbox(&mut on, label).changed() {
+                            *list = on.then(|| global_list.clone());
+                            changed = true;
+                        }
+                        let Some(map) = list else { continue };
+                        egui::Grid::new(("profile_grid", kind, i)).show(ui, |ui| {
+                            for (k, l) in &POSITION_LABELS[1..6] {
+                                ui.label(*l);
+                                let ids = map.entry(k.to_string()).or_default();
+                                let text = self
+                                    .profile_text
+                                    .entry(format!("{}-{}-{}", i, kind, k))
+                                    .or_insert_with(|| hero_names(&self.heroes, ids));
+                                changed |= hero_list_edit(ui, &self.heroes, text, ids);
+                                ui.end_row();
+                            }
+                        });
+                    }
+
+                    let mut on = profile.snipe_list.is_some();
+                    if ui.checkbox(&mut on, "单独的秒选列表").changed() {
+                        profile.snipe_list = on.then(|| global.snipe_list.clone());
+                        changed = true;
+                    }
+                    if let Some(ids) = &mut profile.snipe_list {
+                        let text = self
+                            .profile_text
+                            .entry(format!("{}-snipe", i))
+                            .or_insert_with(|| hero_names(&self.heroes, ids));
+                        changed |= hero_list_edit(ui, &self.heroes, text, ids);
+                    }
+                });
+        }
+        if let Some(i) = rm {
+            self.settings.profiles.remove(i);
+            self.profile_text.clear();
+            changed = true;
+        }
+        if changed {
+            self.trigger_save();
+        }
+    }
+
+    /// 召唤师技能配置：按位置设置，英雄单独配置优先
+    fn spells_card(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(ui.available_width());
+        ui.label(
+            egui::RichText::new("召唤师技能")
+                .color(Color32::from_rgb(200, 170, 110))
+                .strong(),
+        );
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.settings.auto_spells, "自动设置").changed() {
+                self.trigger_save();
+            }
+            if ui.checkbox(&mut self.settings.flash_on_f, "闪现放在 F").changed() {
+                self.trigger_save();
+            }
+        });
+
+        let mut changed = false;
+        egui::Grid::new("spell_grid").striped(true).show(ui, |ui| {
+            let pos = [
+                ("top", "上"),
+                ("jungle", "野"),
+                ("middle", "中"),
+                ("bottom", "下"),
+                ("utility", "辅"),
+                ("aram", "乱斗"),
+            ];
+            for (k, l) in pos {
+                ui.label(l);
+                let pair = self.settings.spells_by_role.entry(k.to_string()).or_insert([4, 14]);
+                changed |= spell_combo(ui, ("role_d", k), &mut pair[0]);
+                changed |= spell_combo(ui, ("role_f", k), &mut pair[1]);
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.spell_champ_input)
+                    .desired_width(90.0)
+                    .hint_text("英雄单独配置"),
+            );
+            let id = lookup_hero_id(&self.heroes, &self.spell_champ_input);
+            if ui.add_enabled(id > 0, egui::Button::new("添加")).clicked() {
+                self.settings.spells_by_champion.entry(id).or_insert([4, 14]);
+                self.spell_champ_input.clear();
+                changed = true;
+            }
+        });
+        let mut champs: Vec<i32> = self.settings.spells_by_champion.keys().copied().collect();
+        champs.sort();
+        let mut rm = None;
+        for id in champs {
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    [60.0, 20.0],
+                    egui::Label::new(lookup_hero_name_by_id(&self.heroes, id)),
+                );
+                let pair = self.settings.spells_by_champion.get_mut(&id).unwrap();
+                changed |= spell_combo(ui, ("champ_d", id), &mut pair[0]);
+                changed |= spell_combo(ui, ("champ_f", id), &mut pair[1]);
+                if ui.button("x").clicked() {
+                    rm = Some(id);
+                }
+            });
+        }
+        if let Some(id) = rm {
+            self.settings.spells_by_champion.remove(&id);
+            changed = true;
+        }
+        if changed {
+            self.trigger_save();
+        }
+    }
+
+    /// 符文预设（从客户端当前符文页导入，可修改符文系与符文ID）与装备方案
+    fn runes_card(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(ui.available_width());
+        ui.label(
+            egui::RichText::new("符文与出装")
+                .color(Color32::from_rgb(200, 170, 110))
+                .strong(),
+        );
+        if ui.checkbox(&mut self.settings.auto_runes, "锁定后自动应用符文").changed() {
+            self.trigger_save();
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.settings.auto_item_sets, "写入装备方案 (data/item_sets)")
+                .changed()
+            {
+                self.trigger_save();
+            }
+            if ui.small_button("清除").clicked() {
+                let _ = self.tx_to_backend.send(BackendMsg::CleanupItemSets);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.rune_champ_input)
+                    .desired_width(70.0)
+                    .hint_text("英雄"),
+            );
+            egui::ComboBox::from_id_salt("rune_pos")
+                .width(40.0)
+                .selected_text(position_label(&self.rune_position))
+                .show_ui(ui, |ui| {
+                    for (k, l) in POSITION_LABELS {
+                        ui.selectable_value(&mut self.rune_position, k.to_string(), l);
+                    }
+                });
+            let id = lookup_hero_id(&self.heroes, &self.rune_champ_input);
+            if ui
+                .add_enabled(id > 0, egui::Button::new("导入当前符文页"))
+                .clicked()
+            {
+                let _ = self.tx_to_backend.send(BackendMsg::ImportRunePage {
+                    champion_id: id,
+                    position: self.rune_position.clone(),
+                });
+                self.rune_champ_input.clear();
+            }
+        });
+
+        let mut changed = false;
+        let mut rm = None;
+        for (i, preset) in self.rune_presets.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} ({})",
+                    lookup_hero_name_by_id(&self.heroes, preset.champion_id),
+                    position_label(&preset.position)
+                ));
+                changed |= style_combo(ui, ("rune_primary", i), &mut preset.primary_style_id);
+                changed |= style_combo(ui, ("rune_sub", i), &mut preset.sub_style_id);
+                if ui.button("x").clicked() {
+                    rm = Some(i);
+                }
+            });
+            let text = self
+                .rune_perk_text
+                .entry((preset.champion_id, preset.position.clone()))
+                .or_insert_with(|| {
+                    preset
+                        .selected_perk_ids
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+            if ui
+                .add(egui::TextEdit::singleline(text).hint_text("符文ID，以空格分隔"))
+                .changed()
+            {
+                preset.selected_perk_ids =
+                    text.split_whitespace().filter_map(|x| x.parse().ok()).collect();
+                changed = true;
+            }
+        }
+        if let Some(i) = rm {
+            self.rune_presets.remove(i);
+            self.rune_perk_text.clear();
+            changed = true;
+        }
+        if changed {
+            let _ = self
+                .tx_to_backend
+                .send(BackendMsg::SaveRunePresets(self.rune_presets.clone()));
+        }
+    }
+
+    /// 皮肤：收藏列表按顺序优先，可从当前英雄已拥有的皮肤中添加
+    fn skins_card(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(ui.available_width());
+        ui.label(
+            egui::RichText::new("皮肤")
+                .color(Color32::from_rgb(200, 170, 110))
+                .strong(),
+        );
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.settings.auto_skin, "自动选择").changed() {
+                self.trigger_save();
+            }
+            if ui.checkbox(&mut self.settings.random_skin, "无收藏时随机").changed() {
+                self.trigger_save();
+            }
+        });
+
+        let picker = egui::CollapsingHeader::new("收藏皮肤")
+            .default_open(false)
+            .show(ui, |ui| self.skin_favourites(ui));
+        let open = !picker.fully_closed();
+        if open != self.skin_picker_open {
+            self.skin_picker_open = open;
+            let _ = self.tx_to_backend.send(BackendMsg::SkinPicker(open));
+        }
+    }
+
+    /// 收藏列表，英雄选择中可从当前英雄已拥有的皮肤中添加
+    fn skin_favourites(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        if let Some((champ_id, skins)) = &self.skin_carousel {
+            ui.horizontal(|ui| {
+                ui.label(lookup_hero_name_by_id(&self.heroes, *champ_id));
+                egui::ComboBox::from_id_salt("skin_fav")
+                    .selected_text("收藏皮肤...")
+                    .show_ui(ui, |ui| {
+                        for (id, name) in skins {
+                            if ui.selectable_label(false, name).clicked() {
+                                let favs = self.settings.favourite_skins.entry(*champ_id).or_default();
+                                if !favs.contains(id) {
+                                    favs.push(*id);
+                                    changed = true;
+                                }
+                            }
+                        }
+                    });
+            });
+        } else {
+            ui.colored_label(Color32::from_gray(120), "英雄选择中可从已拥有的皮肤添加收藏");
+        }
+
+        let mut champs: Vec<i32> = self.settings.favourite_skins.keys().copied().collect();
+        champs.sort();
+        let mut rm = None;
+        for champ_id in champs {
+            let hero = lookup_hero_name_by_id(&self.heroes, champ_id);
+            for &skin_id in &self.settings.favourite_skins[&champ_id] {
+                ui.horizontal(|ui| {
+                    let name = self
+                        .skin_names
+                        .get(&skin_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("{} #{}", hero, skin_id));
+                    ui.label(name);
+                    if ui.button("x").clicked() {
+                        rm = Some((champ_id, skin_id));
+                    }
+                });
+            }
+        }
+        if let Some((champ_id, skin_id)) = rm {
+            if let Some(favs) = self.settings.favourite_skins.get_mut(&champ_id) {
+                favs.retain(|&id| id != skin_id);
+                if favs.is_empty() {
+                    self.settings.favourite_skins.remove(&champ_id);
+                }
+            }
+            changed = true;
+        }
+        if changed {
+            self.trigger_save();
         }
     }
 
@@ -218,6 +1150,10 @@ impl App for HexApp {
                 }
                 GuiMsg::Status(c) => {
                     self.connected = c;
+                    if !c {
+                        self.draft = None;
+                        self.teammates.clear();
+                    }
                     self.status_text = if c {
                         "● 已连接".into()
                     } else {
@@ -225,10 +1161,40 @@ impl App for HexApp {
                     };
                 }
                 GuiMsg::BenchUpdate(ids) => self.bench_ids = ids,
+                GuiMsg::RerollsLeft(n) => self.rerolls_left = Some(n),
+                GuiMsg::Draft(draft) => self.draft = draft,
+                GuiMsg::Teammates(list) => self.teammates = list,
+                GuiMsg::GameRecorded(record) => {
+                    self.history_accounts = history_accounts();
+                    if self.history_account.is_none() {
+                        self.history_account = self
+                            .history_accounts
+                            .iter()
+                            .find(|(_, name)| *name == record.player)
+                            .map(|(puuid, _)| puuid.clone());
+                    }
+                    self.reload_history();
+                }
                 GuiMsg::LoadedData(s) => {
                     self.status_text = s;
                     self.sync_ui_names();
                 }
+                GuiMsg::RunePresets(presets) => {
+                    self.rune_presets = presets;
+                    self.rune_perk_text.clear();
+                }
+                GuiMsg::ActiveProfile { queue_id, profile } => {
+                    self.active_queue = queue_id;
+                    self.active_profile = profile;
+                }
+                GuiMsg::SkinCarousel { champion_id, skins } => {
+                    self.skin_names.extend(skins.iter().cloned());
+                    self.skin_carousel = Some((champion_id, skins));
+                }
+                GuiMsg::ChampionAvailability { pickable, bannable } => {
+                    self.pickable = pickable;
+                    self.bannable = bannable;
+                }
             }
         }
 
@@ -334,6 +1300,25 @@ impl App for HexApp {
                             .rounding(6.0)
                             .stroke(egui::Stroke::new(1.0, Color32::from_rgb(120, 90, 40)));
 
+                        if let Some(queue_id) = self.active_queue {
+                            ui.colored_label(
+                                Color32::from_rgb(10, 203, 230),
+                                format!(
+                                    "当前队列: {} · 方案: {}",
+                                    queue_name(queue_id),
+                                    self.active_profile.as_deref().unwrap_or("全局")
+                                ),
+                            );
+                            ui.add_space(4.0);
+                        }
+
+                        if self.draft.is_some() {
+                            frame_style.show(ui, |ui| self.draft_card(ui, ctx));
+                            ui.add_space(8.0);
+                        }
+                        frame_style.show(ui, |ui| self.teammates_card(ui, ctx));
+                        ui.add_space(8.0);
+
                         frame_style.show(ui, |ui| {
                             ui.set_width(ui.available_width());
                             ui.label(
@@ -357,7 +1342,11 @@ impl App for HexApp {
                                             &self.get_image(ctx, &h.image_name).unwrap(),
                                         )
                                         .fit_to_exact_size(Vec2::splat(32.0));
-                                        if ui.add(egui::Button::image(img)).clicked() {
+                                        let mut resp = ui.add(egui::Button::image(img));
+                                        if let Some(score) = self.tier_scores.get(&h.id) {
+                                            resp = resp.on_hover_text(format!("{} 评分: {}", h.name, score));
+                                        }
+                                        if resp.clicked() {
                                             let _ = self
                                                 .tx_to_backend
                                                 .send(BackendMsg::SwapChamp(h.id));
@@ -389,6 +1378,33 @@ impl App for HexApp {
                                     self.trigger_save();
                                 }
                             });
+                            if self.settings.auto_honor && self.honor_card(ui) {
+                                self.trigger_save();
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("英雄交换:");
+                                if ui
+                                    .checkbox(&mut self.settings.trade_accept, "接受")
+                                    .on_hover_text("对方英雄在预设列表中更靠前时自动接受")
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                                if ui
+                                    .checkbox(&mut self.settings.trade_decline, "拒绝")
+                                    .on_hover_text("会换走预设英雄时自动拒绝")
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                                if ui
+                                    .checkbox(&mut self.settings.trade_request, "请求")
+                                    .on_hover_text("向持有更想玩英雄的队友发起交换")
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                            });
                             ui.horizontal(|ui| {
                                 if ui
                                     .checkbox(&mut self.settings.play_again, "自动回房间")
@@ -461,6 +1477,18 @@ impl App for HexApp {
                                         }
                                     });
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("客户端目录:");
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut self.settings.client_path)
+                                            .hint_text("自动检测"),
+                                    )
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                            });
                         });
                         ui.add_space(8.0);
 
@@ -521,6 +1549,61 @@ impl App for HexApp {
                                 self.settings.snipe_list.remove(i);
                                 self.trigger_save();
                             }
+                            drop(map);
+
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .checkbox(&mut self.settings.tier_scoring, "按评分换英雄")
+                                    .on_hover_text(
+                                        "读取 data/tier_list.json 或 data/tier_list.csv，板凳席英雄分数高出阈值时交换",
+                                    )
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                                ui.label("阈值:");
+                                if ui
+                                    .add(
+                                        egui::DragValue::new(&mut self.settings.tier_threshold)
+                                            .range(0.0..=10.0)
+                                            .speed(0.1),
+                                    )
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                                let mut loaded = format!("已读取 {} 个英雄", self.tier_scores.len());
+                                if !self.tier_unresolved.is_empty() {
+                                    loaded += &format!("\n无法识别: {}", self.tier_unresolved.join("、"));
+                                }
+                                if ui.small_button("重新读取").on_hover_text(loaded).clicked() {
+                                    (self.tier_scores, self.tier_unresolved) = load_tier_scores(&self.heroes);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .checkbox(&mut self.settings.auto_reroll, "自动重随")
+                                    .on_hover_text("当前英雄不在保留列表和秒选列表中时使用重随")
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
+                                match self.rerolls_left {
+                                    Some(n) => ui.label(format!("剩余重随: {}", n)),
+                                    None => ui.colored_label(Color32::GRAY, "剩余重随: -"),
+                                };
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("保留:");
+                                if hero_list_edit(
+                                    ui,
+                                    &self.heroes,
+                                    &mut self.reroll_keep_text,
+                                    &mut self.settings.reroll_keep_list,
+                                ) {
+                                    self.trigger_save();
+                                }
+                            });
                         });
                         ui.add_space(8.0);
 
@@ -566,6 +1649,18 @@ impl App for HexApp {
                                     self.trigger_save();
                                 }
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("换顺序:");
+                                let policy = &mut self.settings.pick_order_swap;
+                                if swap_policy_combo(ui, "pick_order_swap", policy) {
+                                    self.trigger_save();
+                                }
+                                ui.label("换位置:");
+                                let policy = &mut self.settings.position_swap;
+                                if swap_policy_combo(ui, "position_swap", policy) {
+                                    self.trigger_save();
+                                }
+                            });
                             egui::Grid::new("sr_grid").striped(true).show(ui, |ui| {
                                 ui.label("位置");
                                 ui.label("预选");
@@ -586,80 +1681,102 @@ impl App for HexApp {
                                     // 修复部分：不调用 trigger_save，直接发送消息
                                     // Pick Logic
                                     // 声明变量但不初始化，稍后在作用域中赋值，避免未使用赋值警告
-                                    let pick_text;
+                                    // 多个英雄以空格或逗号分隔，按优先级排列
+                                    let pick_ids;
                                     {
                                         let p_t = self.sr_pick_text.entry(k.to_string()).or_default();
                                         // 使用 add_sized 强制设置宽度，解决 Grid 布局中 desired_width 不生效的问题
                                         if ui
-                                            .add_sized([70.0, 20.0], egui::TextEdit::singleline(p_t))
+                                            .add_sized(
+                                                [90.0, 20.0],
+                                                egui::TextEdit::singleline(p_t).hint_text("可填多个"),
+                                            )
                                             .changed()
                                         {
-                                            let id = lookup_hero_id(&self.heroes, p_t);
-                                            self.settings.sr_picks.insert(k.to_string(), id);
+                                            let ids = lookup_hero_ids(&self.heroes, p_t);
+                                            self.settings.sr_picks.insert(k.to_string(), ids);
                                             let _ = self
                                                 .tx_to_backend
                                                 .send(BackendMsg::SaveSettings(self.settings.clone()));
                                         }
-                                        pick_text = p_t.clone();
+                                        pick_ids = self.settings.sr_picks.get(k).cloned().unwrap_or_default();
                                     }
 
-                                    let pick_img_name = lookup_hero_image_by_text(&self.heroes, &pick_text);
-                                    if !pick_img_name.is_empty() {
-                                        if let Some(texture) = self.get_image(ctx, &pick_img_name) {
-                                            ui.image((texture.id(), Vec2::splat(20.0)));
-                                        } else {
-                                            // 图片加载失败或正在下载时，显示占位或保持空白
-                                            ui.label("..."); 
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 2.0;
+                                        for id in pick_ids {
+                                            let playable = self.pickable.as_ref().is_none_or(|p| p.contains(&id));
+                                            self.hero_icon(ui, ctx, id, playable);
                                         }
-                                    } else {
-                                        // 未找到英雄时显示文本（保留反馈）
-                                        ui.colored_label(
-                                            Color32::from_rgb(100, 200, 100),
-                                            lookup_hero_name_by_text(&self.heroes, &pick_text),
-                                        );
-                                    }
+                                    });
 
                                     // Ban Logic
-                                    let ban_text;
+                                    let ban_ids;
                                     {
                                         let b_t = self.sr_ban_text.entry(k.to_string()).or_default();
                                         if ui
-                                            .add_sized([70.0, 20.0], egui::TextEdit::singleline(b_t))
+                                            .add_sized(
+                                                [90.0, 20.0],
+                                                egui::TextEdit::singleline(b_t).hint_text("可填多个"),
+                                            )
                                             .changed()
                                         {
-                                            let id = lookup_hero_id(&self.heroes, b_t);
-                                            self.settings.sr_bans.insert(k.to_string(), id);
+                                            let ids = lookup_hero_ids(&self.heroes, b_t);
+                                            self.settings.sr_bans.insert(k.to_string(), ids);
                                             let _ = self
                                                 .tx_to_backend
                                                 .send(BackendMsg::SaveSettings(self.settings.clone()));
                                         }
-                                        ban_text = b_t.clone();
+                                        ban_ids = self.settings.sr_bans.get(k).cloned().unwrap_or_default();
                                     }
 
-                                    let ban_img_name = lookup_hero_image_by_text(&self.heroes, &ban_text);
-                                    if !ban_img_name.is_empty() {
-                                        if let Some(texture) = self.get_image(ctx, &ban_img_name) {
-                                            ui.image((texture.id(), Vec2::splat(20.0)));
-                                        } else {
-                                            ui.label("...");
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 2.0;
+                                        for id in ban_ids {
+                                            let bannable = self.bannable.as_ref().is_none_or(|b| b.contains(&id));
+                                            self.hero_icon(ui, ctx, id, bannable);
                                         }
-                                    } else {
-                                        ui.colored_label(
-                                            Color32::from_rgb(200, 100, 100),
-                                            lookup_hero_name_by_text(&self.heroes, &ban_text),
-                                        );
-                                    }
+                                    });
 
                                     ui.end_row();
                                 }
                             });
+                            self.availability_hint(ui);
                         });
                         ui.add_space(8.0);
 
+                        frame_style.show(ui, |ui| self.arena_card(ui, ctx));
+                        ui.add_space(8.0);
+
+                        frame_style.show(ui, |ui| self.profiles_card(ui));
+                        ui.add_space(8.0);
+
+                        frame_style.show(ui, |ui| self.chat_card(ui));
+                        ui.add_space(8.0);
+
+                        frame_style.show(ui, |ui| self.spells_card(ui));
+                        ui.add_space(8.0);
+
+                        frame_style.show(ui, |ui| self.runes_card(ui));
+                        ui.add_space(8.0);
+
+                        frame_style.show(ui, |ui| self.skins_card(ui));
+                        ui.add_space(8.0);
+
+                        egui::CollapsingHeader::new("对局记录")
+                            .default_open(false)
+                            .show(ui, |ui| self.history_card(ui, ctx));
+
                         // 使用 CollapsingHeader 构建器
                         egui::CollapsingHeader::new("日志")
                             .default_open(false) // 设置默认收起
                             .show(ui, |ui| {
+                                if ui
+                                    .checkbox(&mut self.settings.capture, "录制请求 (data/captures)")
+                                    .changed()
+                                {
+                                    self.trigger_save();
+                                }
                                 egui::ScrollArea::vertical()
                                     .max_height(100.0)
                                     .stick_to_bottom(true)
@@ -676,6 +1793,150 @@ impl App for HexApp {
                 });
             });
 
-        ctx.request_repaint_after(Duration::from_secs(2));
+        // 英雄选择中刷新倒计时
+        let repaint = if self.draft.is_some() { 100 } else { 2000 };
+        ctx.request_repaint_after(Duration::from_millis(repaint));
+    }
+}
+
+/// 召唤师技能下拉框，返回是否修改
+fn spell_combo(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, value: &mut i32) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id_salt)
+        .width(60.0)
+        .selected_text(spell_name(*value))
+        .show_ui(ui, |ui| {
+            for (id, name) in SUMMONER_SPELLS {
+                changed |= ui.selectable_value(value, id, name).clicked();
+            }
+        });
+    changed
+}
+
+/// 位置ID与显示名称，空字符串表示任意位置
+const POSITION_LABELS: [(&str, &str); 7] = [
+    ("", "任意"),
+    ("top", "上"),
+    ("jungle", "野"),
+    ("middle", "中"),
+    ("bottom", "下"),
+    ("utility", "辅"),
+    ("aram", "乱斗"),
+];
+
+fn position_label(position: &str) -> &str {
+    POSITION_LABELS
+        .iter()
+        .find(|(k, _)| *k == position)
+        .map(|(_, l)| *l)
+        .unwrap_or(position)
+}
+
+/// 符文系下拉框，返回是否修改
+fn style_combo(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, value: &mut i32) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id_salt)
+        .width(50.0)
+        .selected_text(
+            RUNE_STYLES
+                .iter()
+                .find(|(id, _)| id == value)
+                .map(|(_, name)| *name)
+                .unwrap_or("?"),
+        )
+        .show_ui(ui, |ui| {
+            for (id, name) in RUNE_STYLES {
+                changed |= ui.selectable_value(value, id, name).clicked();
+            }
+        });
+    changed
+}
+
+/// 交换请求处理方式下拉框，返回是否修改
+fn swap_policy_combo(ui: &mut egui::Ui, id_salt: &str, value: &mut SwapPolicy) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id_salt)
+        .width(70.0)
+        .selected_text(value.label())
+        .show_ui(ui, |ui| {
+            for p in SwapPolicy::ALL {
+                changed |= ui.selectable_value(value, p, p.label()).clicked();
+            }
+        })
+        .response
+        .on_hover_text("智能：换到更早的选人顺序/有预选英雄的位置时接受，其余拒绝");
+    changed
+}
+
+fn queue_name(queue_id: i64) -> String {
+    QUEUE_NAMES
+        .iter()
+        .find(|(id, _)| *id == queue_id)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("队列 {}", queue_id))
+}
+
+/// 沿用全局 / 开 / 关 三态选择，返回是否修改
+fn tri_state(
+    ui: &mut egui::Ui,
+    id_salt: impl std::hash::Hash,
+    label: &str,
+    value: &mut Option<bool>,
+) -> bool {
+    let text = |v: Option<bool>| match v {
+        None => "沿用",
+        Some(true) => "开",
+        Some(false) => "关",
+    };
+    let mut changed = false;
+    ui.label(label);
+    egui::ComboBox::from_id_salt(id_salt)
+        .width(40.0)
+        .selected_text(text(*value))
+        .show_ui(ui, |ui| {
+            for v in [None, Some(true), Some(false)] {
+                changed |= ui.selectable_value(value, v, text(v)).clicked();
+            }
+        });
+    changed
+}
+
+/// 可选的秒数，未勾选时沿用全局设置
+fn opt_secs(ui: &mut egui::Ui, label: &str, value: &mut Option<u64>, global: u64) -> bool {
+    let mut on = value.is_some();
+    let mut changed = false;
+    if ui.checkbox(&mut on, label).changed() {
+        *value = on.then_some(global);
+        changed = true;
+    }
+    if let Some(v) = value {
+        changed |= ui
+            .add(egui::DragValue::new(v).range(1..=30).suffix("s"))
+            .changed();
+    }
+    changed
+}
+
+fn hero_names(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, ids: &[i32]) -> String {
+    ids.iter()
+        .map(|&id| lookup_hero_name_by_id(heroes, id))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 以空格分隔的英雄列表编辑框，返回是否修改
+fn hero_list_edit(
+    ui: &mut egui::Ui,
+    heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+    text: &mut String,
+    ids: &mut Vec<i32>,
+) -> bool {
+    if ui
+        .add(egui::TextEdit::singleline(text).desired_width(150.0).hint_text("可填多个"))
+        .changed()
+    {
+        *ids = lookup_hero_ids(heroes, text);
+        return true;
     }
+    false
 }
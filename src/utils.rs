@@ -1,4 +1,4 @@
-use crate::models::{Hero, Settings};
+use crate::models::{GameRecord, Hero, ItemBuild, RunePreset, Settings, SUMMONER_SPELLS, TIER_MAP};
 use std::collections::HashMap;
 use std::fs;
 use std::sync::{Arc, Mutex};
@@ -9,6 +9,14 @@ pub const IMG_DIR: &str = "data/img";
 pub const SETTINGS_FILE: &str = "settings.json";
 pub const CHAMP_FILE: &str = "data/champion.json";
 pub const VERSION_FILE: &str = "data/version.txt";
+pub const CAPTURE_DIR: &str = "data/captures";
+pub const RUNES_FILE: &str = "data/runes.json";
+pub const ITEM_SETS_DIR: &str = "data/item_sets";
+pub const TIER_LIST_JSON: &str = "data/tier_list.json";
+pub const TIER_LIST_CSV: &str = "data/tier_list.csv";
+/// 本地战绩，每个账号一个 `<puuid>.jsonl`
+pub const HISTORY_DIR: &str = "data/history";
+pub const EXPORT_DIR: &str = "data/exports";
 
 // --- Helper Functions ---
 pub fn load_settings() -> Settings {
@@ -26,6 +34,166 @@ pub fn save_settings_to_disk(s: &Settings) {
     }
 }
 
+pub fn load_rune_presets() -> Vec<RunePreset> {
+    fs::read_to_string(RUNES_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_rune_presets(presets: &[RunePreset]) {
+    let _ = fs::create_dir_all(DATA_DIR);
+    if let Ok(json) = serde_json::to_string_pretty(presets) {
+        let _ = fs::write(RUNES_FILE, json);
+    }
+}
+
+/// 读取装备方案目录下的全部方案，每个文件可以是单个方案或方案列表
+pub fn load_item_builds() -> Vec<ItemBuild> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ItemBuild),
+        Many(Vec<ItemBuild>),
+    }
+
+    let Ok(entries) = fs::read_dir(ITEM_SETS_DIR) else {
+        return Vec::new();
+    };
+    let mut builds = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let parsed = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        match parsed {
+            Some(OneOrMany::One(b)) => builds.push(b),
+            Some(OneOrMany::Many(list)) => builds.extend(list),
+            None => {}
+        }
+    }
+    builds
+}
+
+/// 账号的本地战绩文件
+pub fn history_path(puuid: &str) -> std::path::PathBuf {
+    std::path::Path::new(HISTORY_DIR).join(format!("{}.jsonl", puuid))
+}
+
+/// 读取本地战绩文件，跳过无法解析的行，按时间从新到旧排列
+pub fn load_game_history(path: &std::path::Path) -> Vec<GameRecord> {
+    let mut records: Vec<GameRecord> = fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    records.reverse();
+    records
+}
+
+/// 本地保存了战绩的账号: (puuid, 最近的玩家名称)
+pub fn history_accounts() -> Vec<(String, String)> {
+    let Ok(entries) = fs::read_dir(HISTORY_DIR) else {
+        return Vec::new();
+    };
+    let mut accounts = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "jsonl") {
+            continue;
+        }
+        let Some(puuid) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let name = load_game_history(&path)
+            .into_iter()
+            .map(|r| r.player)
+            .find(|p| !p.is_empty())
+            .unwrap_or_else(|| puuid.to_string());
+        accounts.push((puuid.to_string(), name));
+    }
+    accounts.sort_by(|a, b| a.1.cmp(&b.1));
+    accounts
+}
+
+/// 读取大乱斗评分表，返回评分与无法识别的英雄名
+///
+/// 支持 `data/tier_list.json`（`{"英雄名或ID": 分数}`）和 `data/tier_list.csv`
+/// （每行 `英雄名或ID,分数`），两者都存在时合并。分数可以是数字，
+/// 也可以是 S+/S/A/B/C/D 等级（对应 6~1 分）；无法识别的行（如表头）会被忽略。
+pub fn load_tier_scores(heroes: &Arc<Mutex<HashMap<i32, Hero>>>) -> (HashMap<i32, f64>, Vec<String>) {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let json = fs::read_to_string(TIER_LIST_JSON)
+        .ok()
+        .and_then(|c| serde_json::from_str::<HashMap<String, serde_json::Value>>(&c).ok());
+    for (champ, score) in json.into_iter().flatten() {
+        let score = match score {
+            serde_json::Value::String(s) => s,
+            v => v.to_string(),
+        };
+        entries.push((champ, score));
+    }
+    if let Ok(content) = fs::read_to_string(TIER_LIST_CSV) {
+        for line in content.lines() {
+            let mut cols = line.split([',', '\t']);
+            if let (Some(champ), Some(score)) = (cols.next(), cols.next()) {
+                entries.push((champ.trim().to_string(), score.trim().to_string()));
+            }
+        }
+    }
+    resolve_tier_scores(entries, heroes)
+}
+
+/// 把评分表条目解析为英雄ID与分数
+///
+/// 英雄名只做完整匹配（名称或别名中的一项），不按部分文字猜测，
+/// 以免简称在每次运行时对应到不同的英雄。有分数但找不到英雄的名字单独返回。
+fn resolve_tier_scores(
+    entries: Vec<(String, String)>,
+    heroes: &Arc<Mutex<HashMap<i32, Hero>>>,
+) -> (HashMap<i32, f64>, Vec<String>) {
+    let mut scores = HashMap::new();
+    let mut unresolved = Vec::new();
+    for (champ, score) in entries {
+        let score = score.parse::<f64>().ok().or(match score.to_uppercase().as_str() {
+            "S+" => Some(6.0),
+            "S" => Some(5.0),
+            "A" => Some(4.0),
+            "B" => Some(3.0),
+            "C" => Some(2.0),
+            "D" => Some(1.0),
+            _ => None,
+        });
+        let Some(score) = score else {
+            continue;
+        };
+        let id = champ
+            .parse::<i32>()
+            .unwrap_or_else(|_| lookup_hero_id_exact(heroes, &champ));
+        if id > 0 {
+            scores.insert(id, score);
+        } else {
+            unresolved.push(champ);
+        }
+    }
+    unresolved.sort();
+    (scores, unresolved)
+}
+
+/// 按名称或别名中的一项完整匹配英雄（不区分大小写），找不到时返回 0
+pub fn lookup_hero_id_exact(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> i32 {
+    let map = heroes.lock().unwrap();
+    let text = text.trim();
+    let lower = text.to_lowercase();
+    if lower.is_empty() {
+        return 0;
+    }
+    map.values()
+        .find(|h| h.name == text || h.alias.split_whitespace().any(|s| s == lower))
+        .map_or(0, |h| h.id)
+}
+
 pub fn lookup_hero_id(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> i32 {
     let map = heroes.lock().unwrap();
     let lower = text.trim().to_lowercase();
@@ -43,13 +211,16 @@ pub fn lookup_hero_id(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> i3
     0
 }
 
-pub fn lookup_hero_name_by_text(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> String {
-    let id = lookup_hero_id(heroes, text);
-    if id == 0 {
-        return String::new();
+/// 解析以空格、逗号或顿号分隔的多个英雄，保持输入顺序并去重
+pub fn lookup_hero_ids(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> Vec<i32> {
+    let mut ids = Vec::new();
+    for part in text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '，' | '、')) {
+        let id = lookup_hero_id(heroes, part);
+        if id > 0 && !ids.contains(&id) {
+            ids.push(id);
+        }
     }
-    let map = heroes.lock().unwrap();
-    map.get(&id).map(|h| h.name.clone()).unwrap_or_default()
+    ids
 }
 
 pub fn lookup_hero_name_by_id(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, id: i32) -> String {
@@ -60,12 +231,70 @@ pub fn lookup_hero_name_by_id(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, id: i32)
     map.get(&id).map(|h| h.name.clone()).unwrap_or_default()
 }
 
-/// 根据输入文本查找英雄头像图片名
-pub fn lookup_hero_image_by_text(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, text: &str) -> String {
-    let id = lookup_hero_id(heroes, text);
-    if id == 0 {
-        return String::new();
-    }
+/// 根据英雄ID查找头像图片名
+pub fn lookup_hero_image_by_id(heroes: &Arc<Mutex<HashMap<i32, Hero>>>, id: i32) -> String {
     let map = heroes.lock().unwrap();
     map.get(&id).map(|h| h.image_name.clone()).unwrap_or_default()
 }
+
+/// 根据召唤师技能ID查找名称
+pub fn spell_name(id: i32) -> &'static str {
+    SUMMONER_SPELLS
+        .iter()
+        .find(|(sid, _)| *sid == id)
+        .map(|(_, name)| *name)
+        .unwrap_or("?")
+}
+
+/// 段位数据中的单双排段位，如 "荣耀黄金 II"，没有段位时为 "未定级"
+pub fn solo_rank_label(stats: &serde_json::Value) -> String {
+    let solo = stats
+        .get("queueMap")
+        .and_then(|m| m.get("RANKED_SOLO_5x5"));
+    let tier = solo
+        .and_then(|q| q.get("tier"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if tier.is_empty() || tier == "NONE" {
+        return "未定级".to_string();
+    }
+    let tier_name = TIER_MAP
+        .iter()
+        .find(|(k, _)| *k == tier)
+        .map(|(_, v)| v.to_string())
+        .unwrap_or_else(|| tier.to_string());
+    match solo.and_then(|q| q.get("division")).and_then(|v| v.as_str()) {
+        Some(div) if div != "NA" && !div.is_empty() => format!("{} {}", tier_name, div),
+        _ => tier_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heroes() -> Arc<Mutex<HashMap<i32, Hero>>> {
+        let hero = |id: i32, name: &str, alias: &str| Hero {
+            id,
+            name: name.into(),
+            alias: alias.into(),
+            image_name: String::new(),
+        };
+        Arc::new(Mutex::new(HashMap::from([
+            (67, hero(67, "暗夜猎手", "暗夜猎手 薇恩 vayne")),
+            (222, hero(222, "暴走萝莉", "暴走萝莉 金克丝 jinx")),
+            (202, hero(202, "戏命师", "戏命师 烬 jhin")),
+        ])))
+    }
+
+    #[test]
+    fn tier_list_names_match_exactly() {
+        let entries = [("Jinx", "S"), ("薇恩", "4.5"), ("J", "A"), ("暴走", "B"), ("202", "3"), ("英雄", "评分")];
+        let entries = entries.iter().map(|(c, s)| (c.to_string(), s.to_string())).collect();
+
+        let (scores, unresolved) = resolve_tier_scores(entries, &heroes());
+
+        assert_eq!(scores, HashMap::from([(222, 5.0), (67, 4.5), (202, 3.0)]));
+        assert_eq!(unresolved, ["J", "暴走"]);
+    }
+}